@@ -1,24 +1,104 @@
-/// Determine the length of the collatz sequence beginning at `n`.
-fn collatz_length(mut n: i32) -> u32 {
-    let mut length = 0;
-    while n > 1 {
-        length += 1;
-        if n % 2 == 0 {
-            n = n / 2
-        } else {
-            n = 3 * n + 1
-        }
-    }
+use collatz::{
+    collatz_length, stopping_time_histogram, write_histogram_csv, InverseTree, Verifier,
+};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: collatz [report START..END [OUTPUT.csv]]
+       collatz tree DEPTH [--color]
+       collatz verify START..END CHECKPOINT";
+
+/// Values verified between two checkpoints.
+const CHECKPOINT_INTERVAL: u64 = 1 << 20;
+
+/// Parse an exclusive range like `1..1000`.
+fn parse_range(arg: &str) -> Option<Range<u64>> {
+    let (start, end) = arg.split_once("..")?;
+    Some(start.parse().ok()?..end.parse().ok()?)
+}
+
+/// Write a histogram of stopping times over `range` as CSV, to `output` or
+/// stdout.
+fn report(range: Range<u64>, output: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let histogram = stopping_time_histogram(range)?;
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    write_histogram_csv(&mut out, &histogram)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the inverse tree down to `depth` as Graphviz DOT to stdout.
+fn tree(depth: u32, colored: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let tree = InverseTree::new(depth)?;
+    let mut out = io::stdout().lock();
+    tree.write_dot(&mut out, colored)?;
+    out.flush()?;
+    Ok(())
+}
 
-    return length;
+/// Verify `range`, resuming from and saving progress to `checkpoint`.
+fn verify(range: Range<u64>, checkpoint: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut verifier = Verifier::open(range, checkpoint, CHECKPOINT_INTERVAL)?;
+    if verifier.state().next > verifier.state().range.start {
+        eprintln!("resuming at {}", verifier.state().next);
+    }
+    for record in verifier.run()? {
+        println!("{} {}", record.start, record.length);
+    }
+    Ok(())
 }
 
-fn main() {
-    println!("Collatz length of 3 is {}", collatz_length(3))
+fn exit_code(result: Result<(), Box<dyn std::error::Error>>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("collatz: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-#[test]
-fn test_collatz_length() {
-    assert_eq!(collatz_length(1), 0);
-    assert_eq!(collatz_length(3), 7);
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] => {
+            match collatz_length(3u32) {
+                Ok(length) => println!("Collatz length of 3 is {length}"),
+                Err(err) => eprintln!("{err}"),
+            }
+            ExitCode::SUCCESS
+        }
+        ["report", range, output @ ..] if output.len() <= 1 => {
+            let Some(range) = parse_range(range) else {
+                eprintln!("invalid range {range:?}\n{USAGE}");
+                return ExitCode::from(2);
+            };
+            exit_code(report(range, output.first().copied()))
+        }
+        ["tree", depth, flags @ ..] if matches!(flags, [] | ["--color"]) => {
+            let Ok(depth) = depth.parse() else {
+                eprintln!("invalid depth {depth:?}\n{USAGE}");
+                return ExitCode::from(2);
+            };
+            exit_code(tree(depth, !flags.is_empty()))
+        }
+        ["verify", range, checkpoint] => {
+            let Some(range) = parse_range(range) else {
+                eprintln!("invalid range {range:?}\n{USAGE}");
+                return ExitCode::from(2);
+            };
+            exit_code(verify(range, checkpoint))
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
 }
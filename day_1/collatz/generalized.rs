@@ -0,0 +1,181 @@
+use crate::CollatzError;
+
+/// A generalized collatz map on the positive integers:
+///
+/// - `n / divisor` if `n` is divisible by `divisor`,
+/// - `multiplier * n + increment` otherwise.
+///
+/// The classic sequence is `divisor = 2, multiplier = 3, increment = 1`. The
+/// 3x+1 map on the negative integers corresponds to `increment = -1`, as
+/// `-(3 * n - 1) = 3 * (-n) + 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralizedMap {
+    divisor: u64,
+    multiplier: u64,
+    increment: i64,
+}
+
+/// How the sequence of a `GeneralizedMap` ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The sequence reached 1.
+    ReachesOne,
+    /// The sequence entered a cycle not containing 1. The cycle starts at its
+    /// smallest element.
+    Cycle(Vec<u64>),
+    /// The step limit was hit or a term did not fit into a `u64`, so the
+    /// sequence probably diverges.
+    ExceededBound,
+}
+
+impl GeneralizedMap {
+    /// Returns an error if the map could leave the positive integers.
+    pub fn new(divisor: u64, multiplier: u64, increment: i64) -> Result<Self, CollatzError> {
+        if divisor < 2 {
+            return Err(CollatzError::InvalidMap("the divisor must be at least 2"));
+        }
+        if multiplier == 0 {
+            return Err(CollatzError::InvalidMap("the multiplier must be positive"));
+        }
+        // multiplier * n + increment > 0 for all n >= 1.
+        if i128::from(multiplier) + i128::from(increment) <= 0 {
+            return Err(CollatzError::InvalidMap(
+                "multiplier + increment must be positive",
+            ));
+        }
+        Ok(Self {
+            divisor,
+            multiplier,
+            increment,
+        })
+    }
+
+    /// The classic 3x+1 map.
+    pub fn collatz() -> Self {
+        Self::new(2, 3, 1).unwrap()
+    }
+
+    /// Calculate the next term, or `None` if it does not fit into a `u64`.
+    pub fn step(&self, n: u64) -> Option<u64> {
+        if n.is_multiple_of(self.divisor) {
+            Some(n / self.divisor)
+        } else {
+            let next = i128::from(self.multiplier) * i128::from(n) + i128::from(self.increment);
+            u64::try_from(next).ok()
+        }
+    }
+
+    /// Follow the sequence beginning at `start` for at most `max_steps`
+    /// steps, detecting cycles with Brent's algorithm in constant memory.
+    pub fn orbit(&self, start: u64, max_steps: u64) -> Result<Outcome, CollatzError> {
+        if start == 0 {
+            return Err(CollatzError::NonPositive);
+        }
+        if start == 1 {
+            return Ok(Outcome::ReachesOne);
+        }
+
+        let mut power = 1;
+        let mut cycle_length = 1;
+        let mut tortoise = start;
+        let Some(mut hare) = self.step(start) else {
+            return Ok(Outcome::ExceededBound);
+        };
+        let mut steps = 1;
+        while tortoise != hare {
+            if hare == 1 {
+                return Ok(Outcome::ReachesOne);
+            }
+            if steps >= max_steps {
+                return Ok(Outcome::ExceededBound);
+            }
+            if power == cycle_length {
+                tortoise = hare;
+                power *= 2;
+                cycle_length = 0;
+            }
+            hare = match self.step(hare) {
+                Some(next) => next,
+                None => return Ok(Outcome::ExceededBound),
+            };
+            cycle_length += 1;
+            steps += 1;
+        }
+        if hare == 1 {
+            return Ok(Outcome::ReachesOne);
+        }
+
+        // `hare` is on the cycle, so walking `cycle_length` steps visits all of it.
+        let mut cycle = Vec::with_capacity(cycle_length);
+        let mut n = hare;
+        for _ in 0..cycle_length {
+            cycle.push(n);
+            n = self.step(n).expect("cycle terms were already calculated");
+        }
+        if cycle.contains(&1) {
+            return Ok(Outcome::ReachesOne);
+        }
+        let smallest = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+        cycle.rotate_left(smallest);
+        Ok(Outcome::Cycle(cycle))
+    }
+}
+
+#[test]
+fn test_invalid_maps() {
+    assert!(matches!(
+        GeneralizedMap::new(1, 3, 1),
+        Err(CollatzError::InvalidMap(_))
+    ));
+    assert!(matches!(
+        GeneralizedMap::new(2, 0, 1),
+        Err(CollatzError::InvalidMap(_))
+    ));
+    assert!(matches!(
+        GeneralizedMap::new(2, 3, -3),
+        Err(CollatzError::InvalidMap(_))
+    ));
+    assert_eq!(
+        GeneralizedMap::collatz().orbit(0, 100),
+        Err(CollatzError::NonPositive)
+    );
+}
+
+#[test]
+fn test_collatz_reaches_one() {
+    let map = GeneralizedMap::collatz();
+    for n in 1..1000 {
+        assert_eq!(map.orbit(n, 1000), Ok(Outcome::ReachesOne), "{n}");
+    }
+    assert_eq!(map.orbit(27, 50), Ok(Outcome::ExceededBound));
+}
+
+#[test]
+fn test_five_x_plus_one() {
+    let map = GeneralizedMap::new(2, 5, 1).unwrap();
+    assert_eq!(map.orbit(3, 100), Ok(Outcome::ReachesOne));
+    assert_eq!(
+        map.orbit(13, 100),
+        Ok(Outcome::Cycle(vec![
+            13, 66, 33, 166, 83, 416, 208, 104, 52, 26
+        ]))
+    );
+    assert_eq!(map.orbit(7, 10_000), Ok(Outcome::ExceededBound));
+}
+
+#[test]
+fn test_negative_integers() {
+    // 3n - 1 on positive n is 3n + 1 on -n.
+    let map = GeneralizedMap::new(2, 3, -1).unwrap();
+    assert_eq!(map.orbit(3, 100), Ok(Outcome::ReachesOne));
+    assert_eq!(
+        map.orbit(7, 100),
+        Ok(Outcome::Cycle(vec![5, 14, 7, 20, 10]))
+    );
+    assert_eq!(
+        map.orbit(17, 100),
+        Ok(Outcome::Cycle(vec![
+            17, 50, 25, 74, 37, 110, 55, 164, 82, 41, 122, 61, 182, 91, 272, 136, 68, 34
+        ]))
+    );
+}
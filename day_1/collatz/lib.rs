@@ -0,0 +1,129 @@
+use thiserror::Error;
+
+mod generalized;
+mod search;
+mod stats;
+mod tree;
+mod verify;
+
+pub use generalized::{GeneralizedMap, Outcome};
+pub use search::{longest_chain_below, longest_chain_below_with_threads, Chain};
+pub use stats::{
+    glide, max_excursion, stopping_time_histogram, total_stopping_time, write_histogram_csv,
+    CollatzSeq,
+};
+pub use tree::InverseTree;
+pub use verify::{Checkpoint, Verifier, VerifyError};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CollatzError {
+    #[error("the collatz sequence is only defined for positive integers")]
+    NonPositive,
+    #[error("3 * {0} + 1 overflows the integer type")]
+    Overflow(u128),
+    #[error("2 * {0} overflows the integer type")]
+    DoublingOverflow(u128),
+    #[error("invalid map: {0}")]
+    InvalidMap(&'static str),
+}
+
+/// An unsigned integer type the collatz sequence can be calculated in.
+pub trait CollatzInt: Copy + Eq + Into<u128> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn is_even(self) -> bool;
+
+    fn half(self) -> Self;
+
+    /// Calculate `3 * self + 1`, or `None` on overflow.
+    fn checked_triple_plus_one(self) -> Option<Self>;
+}
+
+macro_rules! impl_collatz_int {
+    ($($t:ty),*) => {
+        $(
+            impl CollatzInt for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn is_even(self) -> bool {
+                    self % 2 == 0
+                }
+
+                fn half(self) -> Self {
+                    self / 2
+                }
+
+                fn checked_triple_plus_one(self) -> Option<Self> {
+                    self.checked_mul(3)?.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_collatz_int!(u8, u16, u32, u64, u128);
+
+/// Calculate the term following `n` in the collatz sequence.
+pub fn collatz_step<T: CollatzInt>(n: T) -> Result<T, CollatzError> {
+    if n == T::ZERO {
+        return Err(CollatzError::NonPositive);
+    }
+    if n.is_even() {
+        Ok(n.half())
+    } else {
+        n.checked_triple_plus_one()
+            .ok_or(CollatzError::Overflow(n.into()))
+    }
+}
+
+/// Determine the length of the collatz sequence beginning at `n`.
+///
+/// Returns an error for zero, and if an intermediate term does not fit into
+/// `T`; use a wider type in that case.
+pub fn collatz_length<T: CollatzInt>(mut n: T) -> Result<u32, CollatzError> {
+    if n == T::ZERO {
+        return Err(CollatzError::NonPositive);
+    }
+    let mut length = 0;
+    while n != T::ONE {
+        length += 1;
+        n = collatz_step(n)?;
+    }
+    Ok(length)
+}
+
+#[test]
+fn test_collatz_length() {
+    assert_eq!(collatz_length(1u32), Ok(0));
+    assert_eq!(collatz_length(3u32), Ok(7));
+    assert_eq!(collatz_length(27u64), Ok(111));
+    assert_eq!(collatz_length(837_799u128), Ok(524));
+}
+
+#[test]
+fn test_collatz_length_non_positive() {
+    assert_eq!(collatz_length(0u32), Err(CollatzError::NonPositive));
+    assert_eq!(collatz_length(0u128), Err(CollatzError::NonPositive));
+}
+
+#[test]
+fn test_collatz_length_overflow() {
+    // The i32 version overflowed here, u32 is still wide enough.
+    assert_eq!(collatz_length(113_383u32), Ok(247));
+    // This sequence peaks at 17_202_377_752.
+    assert_eq!(
+        collatz_length(159_487u32),
+        Err(CollatzError::Overflow(1_699_000_271))
+    );
+    assert_eq!(collatz_length(159_487u64), Ok(183));
+    assert_eq!(
+        collatz_length(u64::MAX),
+        Err(CollatzError::Overflow(u64::MAX.into()))
+    );
+    assert_eq!(
+        collatz_length(u128::MAX),
+        Err(CollatzError::Overflow(u128::MAX))
+    );
+}
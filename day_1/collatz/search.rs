@@ -0,0 +1,191 @@
+use std::sync::atomic::{AtomicU16, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::{collatz_step, CollatzError};
+
+/// Starting values handed to a worker at a time.
+const CHUNK_SIZE: u64 = 1 << 14;
+
+/// Upper bound for the memo table, which needs two bytes per entry.
+const MAX_MEMO_LEN: u64 = 1 << 26;
+
+/// A starting value together with the length of its collatz sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chain {
+    pub start: u64,
+    pub length: u32,
+}
+
+impl Chain {
+    /// Whether `self` beats `other`; ties go to the smaller start value so the
+    /// result does not depend on thread scheduling.
+    fn is_better_than(&self, other: &Chain) -> bool {
+        (self.length, std::cmp::Reverse(self.start))
+            > (other.length, std::cmp::Reverse(other.start))
+    }
+}
+
+/// Stopping times of small values, shared by all workers. Zero means
+/// "unknown", which is fine as only `1` has a stopping time of zero.
+struct Memo {
+    lengths: Vec<AtomicU16>,
+}
+
+impl Memo {
+    fn new(len: u64) -> Self {
+        Self {
+            lengths: (0..len).map(|_| AtomicU16::new(0)).collect(),
+        }
+    }
+
+    fn get(&self, n: u64) -> Option<u32> {
+        let length = self.lengths.get(usize::try_from(n).ok()?)?;
+        match length.load(Ordering::Relaxed) {
+            0 => None,
+            length => Some(u32::from(length)),
+        }
+    }
+
+    /// Racing writers always store the same value, so relaxed ordering is
+    /// enough.
+    fn set(&self, n: u64, length: u32) {
+        let slot = usize::try_from(n).ok().and_then(|n| self.lengths.get(n));
+        if let (Some(slot), Ok(length)) = (slot, u16::try_from(length)) {
+            slot.store(length, Ordering::Relaxed);
+        }
+    }
+
+    /// Determine the length of the sequence beginning at `n`, recording it
+    /// for every small value on the path that was not known yet.
+    fn length(&self, n: u64, path: &mut Vec<u64>) -> Result<u32, CollatzError> {
+        path.clear();
+        let mut current = n;
+        let mut known = 0;
+        while current != 1 {
+            if let Some(length) = self.get(current) {
+                known = length;
+                break;
+            }
+            path.push(current);
+            current = collatz_step(current)?;
+        }
+        for (steps_to_known, &value) in path.iter().rev().enumerate() {
+            self.set(value, known + steps_to_known as u32 + 1);
+        }
+        Ok(known + path.len() as u32)
+    }
+}
+
+/// Find the start value below `limit` with the longest collatz sequence.
+///
+/// The range is split into chunks which are processed by one worker per
+/// available CPU, sharing a memo table of already known stopping times.
+/// Returns `None` if `limit` is at most 1.
+pub fn longest_chain_below(limit: u64) -> Result<Option<Chain>, CollatzError> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    longest_chain_below_with_threads(limit, threads)
+}
+
+/// Like `longest_chain_below`, with an explicit number of worker threads.
+pub fn longest_chain_below_with_threads(
+    limit: u64,
+    threads: usize,
+) -> Result<Option<Chain>, CollatzError> {
+    let memo = Memo::new(limit.min(MAX_MEMO_LEN));
+    let next_chunk = AtomicU64::new(1);
+    let best: Mutex<Option<Chain>> = Mutex::new(None);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                scope.spawn(|| -> Result<(), CollatzError> {
+                    let mut path = Vec::new();
+                    let mut local_best: Option<Chain> = None;
+                    loop {
+                        let start = next_chunk.fetch_add(CHUNK_SIZE, Ordering::Relaxed);
+                        if start >= limit {
+                            break;
+                        }
+                        for n in start..limit.min(start.saturating_add(CHUNK_SIZE)) {
+                            let chain = Chain {
+                                start: n,
+                                length: memo.length(n, &mut path)?,
+                            };
+                            if local_best.is_none_or(|b| chain.is_better_than(&b)) {
+                                local_best = Some(chain);
+                            }
+                        }
+                    }
+                    let mut best = best.lock().unwrap();
+                    if let Some(chain) = local_best {
+                        if best.is_none_or(|b| chain.is_better_than(&b)) {
+                            *best = Some(chain);
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().unwrap())
+    })?;
+
+    Ok(best.into_inner().unwrap())
+}
+
+#[test]
+fn test_longest_chain_small_limits() {
+    assert_eq!(longest_chain_below(0), Ok(None));
+    assert_eq!(longest_chain_below(1), Ok(None));
+    assert_eq!(
+        longest_chain_below(2),
+        Ok(Some(Chain {
+            start: 1,
+            length: 0
+        }))
+    );
+    assert_eq!(
+        longest_chain_below(10),
+        Ok(Some(Chain {
+            start: 9,
+            length: 19
+        }))
+    );
+    assert_eq!(
+        longest_chain_below(100),
+        Ok(Some(Chain {
+            start: 97,
+            length: 118
+        }))
+    );
+}
+
+#[test]
+fn test_longest_chain_matches_collatz_length() {
+    let limit = 20_000;
+    let expected = (1..limit)
+        .map(|n| Chain {
+            start: n,
+            length: crate::collatz_length(n).unwrap(),
+        })
+        .reduce(|a, b| if b.is_better_than(&a) { b } else { a });
+    for threads in [1, 3, 8] {
+        assert_eq!(
+            longest_chain_below_with_threads(limit, threads),
+            Ok(expected)
+        );
+    }
+}
+
+#[test]
+fn test_longest_chain_below_one_million() {
+    assert_eq!(
+        longest_chain_below(1_000_000),
+        Ok(Some(Chain {
+            start: 837_799,
+            length: 524
+        }))
+    );
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crate::{collatz_length, collatz_step, CollatzError, CollatzInt};
+
+/// An iterator over the terms of the collatz sequence beginning at `n`,
+/// including `n` itself and the final `1`.
+///
+/// If a term cannot be calculated, the error is yielded once and the
+/// iteration ends.
+pub struct CollatzSeq<T: CollatzInt> {
+    next: Option<Result<T, CollatzError>>,
+}
+
+impl<T: CollatzInt> CollatzSeq<T> {
+    pub fn new(n: T) -> Self {
+        let first = if n == T::ZERO {
+            Err(CollatzError::NonPositive)
+        } else {
+            Ok(n)
+        };
+        Self { next: Some(first) }
+    }
+}
+
+impl<T: CollatzInt> Iterator for CollatzSeq<T> {
+    type Item = Result<T, CollatzError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        if let Ok(n) = current {
+            if n != T::ONE {
+                self.next = Some(collatz_step(n));
+            }
+        }
+        Some(current)
+    }
+}
+
+/// The number of steps needed to reach 1, the same as `collatz_length`.
+pub fn total_stopping_time<T: CollatzInt>(n: T) -> Result<u32, CollatzError> {
+    collatz_length(n)
+}
+
+/// The number of steps until the sequence first drops below `n`. The glide
+/// of 1 is 0.
+pub fn glide<T: CollatzInt>(n: T) -> Result<u32, CollatzError> {
+    if n == T::ZERO {
+        return Err(CollatzError::NonPositive);
+    }
+    let start: u128 = n.into();
+    let mut steps = 0;
+    for term in CollatzSeq::new(n).skip(1) {
+        steps += 1;
+        if term?.into() < start {
+            break;
+        }
+    }
+    Ok(steps)
+}
+
+/// The largest term of the sequence beginning at `n`.
+pub fn max_excursion<T: CollatzInt>(n: T) -> Result<T, CollatzError> {
+    let mut max = n;
+    for term in CollatzSeq::new(n) {
+        let term = term?;
+        if term.into() > max.into() {
+            max = term;
+        }
+    }
+    Ok(max)
+}
+
+/// Count how many starting values in `range` have each total stopping time.
+pub fn stopping_time_histogram(range: Range<u64>) -> Result<BTreeMap<u32, u64>, CollatzError> {
+    let mut histogram = BTreeMap::new();
+    for n in range {
+        *histogram.entry(collatz_length(n)?).or_insert(0) += 1;
+    }
+    Ok(histogram)
+}
+
+/// Write a histogram as CSV with a `stopping_time,count` header.
+pub fn write_histogram_csv(out: &mut impl Write, histogram: &BTreeMap<u32, u64>) -> io::Result<()> {
+    writeln!(out, "stopping_time,count")?;
+    for (stopping_time, count) in histogram {
+        writeln!(out, "{stopping_time},{count}")?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_collatz_seq() {
+    let terms: Result<Vec<u32>, _> = CollatzSeq::new(3u32).collect();
+    assert_eq!(terms, Ok(vec![3, 10, 5, 16, 8, 4, 2, 1]));
+    let terms: Result<Vec<u64>, _> = CollatzSeq::new(1u64).collect();
+    assert_eq!(terms, Ok(vec![1]));
+    let terms: Vec<_> = CollatzSeq::new(0u32).collect();
+    assert_eq!(terms, [Err(CollatzError::NonPositive)]);
+}
+
+#[test]
+fn test_collatz_seq_overflow() {
+    let mut terms = CollatzSeq::new(u8::MAX);
+    assert_eq!(terms.next(), Some(Ok(u8::MAX)));
+    assert_eq!(terms.next(), Some(Err(CollatzError::Overflow(255))));
+    assert_eq!(terms.next(), None);
+}
+
+#[test]
+fn test_stopping_times() {
+    assert_eq!(total_stopping_time(27u32), Ok(111));
+    assert_eq!(glide(1u32), Ok(0));
+    assert_eq!(glide(2u32), Ok(1));
+    assert_eq!(glide(3u32), Ok(6));
+    assert_eq!(glide(27u32), Ok(96));
+    assert_eq!(glide(0u32), Err(CollatzError::NonPositive));
+    assert_eq!(max_excursion(1u32), Ok(1));
+    assert_eq!(max_excursion(3u32), Ok(16));
+    assert_eq!(max_excursion(27u32), Ok(9232));
+    assert_eq!(max_excursion(159_487u64), Ok(17_202_377_752));
+    assert_eq!(
+        max_excursion(159_487u32),
+        Err(CollatzError::Overflow(1_699_000_271))
+    );
+}
+
+#[test]
+fn test_histogram_csv() {
+    let histogram = stopping_time_histogram(1..9).unwrap();
+    // 1: 0, 2: 1, 3: 7, 4: 2, 5: 5, 6: 8, 7: 16, 8: 3
+    assert_eq!(histogram.values().sum::<u64>(), 8);
+    let mut out = Vec::new();
+    write_histogram_csv(&mut out, &histogram).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "stopping_time,count\n0,1\n1,1\n2,1\n3,1\n5,1\n7,1\n8,1\n16,1\n"
+    );
+    assert_eq!(
+        stopping_time_histogram(0..3),
+        Err(CollatzError::NonPositive)
+    );
+}
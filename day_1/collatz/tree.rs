@@ -0,0 +1,119 @@
+use std::io::{self, Write};
+
+use crate::CollatzError;
+
+/// Graphviz color scheme used when coloring nodes, with 9 shades.
+const COLOR_SCHEME: &str = "ylorrd9";
+const COLOR_COUNT: u32 = 9;
+
+/// The inverse collatz tree: all values whose sequence reaches 1 within a
+/// given number of steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InverseTree {
+    /// Every value with its stopping time, which is its depth in the tree,
+    /// in breadth-first order.
+    nodes: Vec<(u64, u32)>,
+    depth: u32,
+}
+
+/// The values whose next collatz term is `n`.
+fn predecessors(n: u64) -> Result<Vec<u64>, CollatzError> {
+    let double = n
+        .checked_mul(2)
+        .ok_or(CollatzError::DoublingOverflow(n.into()))?;
+    let mut predecessors = vec![double];
+    // (n - 1) / 3 must be odd, and 1 would just close the 1 -> 4 -> 2 -> 1 loop.
+    if n % 6 == 4 && n > 4 {
+        predecessors.push((n - 1) / 3);
+    }
+    Ok(predecessors)
+}
+
+impl InverseTree {
+    /// Build the tree from 1 down to `depth` levels.
+    pub fn new(depth: u32) -> Result<Self, CollatzError> {
+        let mut nodes = vec![(1, 0)];
+        let mut level_start = 0;
+        for level in 1..=depth {
+            let level_end = nodes.len();
+            for i in level_start..level_end {
+                for predecessor in predecessors(nodes[i].0)? {
+                    nodes.push((predecessor, level));
+                }
+            }
+            level_start = level_end;
+        }
+        Ok(Self { nodes, depth })
+    }
+
+    /// All values in the tree with their stopping times, breadth first.
+    pub fn nodes(&self) -> &[(u64, u32)] {
+        &self.nodes
+    }
+
+    /// Write the tree as a Graphviz DOT digraph, with edges pointing from
+    /// each value to its successor in the collatz sequence. If `colored`,
+    /// nodes are shaded by stopping time.
+    pub fn write_dot(&self, out: &mut impl Write, colored: bool) -> io::Result<()> {
+        writeln!(out, "digraph collatz {{")?;
+        writeln!(out, "    rankdir=BT;")?;
+        if colored {
+            writeln!(out, "    node [style=filled, colorscheme={COLOR_SCHEME}];")?;
+        }
+        for &(n, stopping_time) in &self.nodes {
+            if colored {
+                let shade = 1 + stopping_time * (COLOR_COUNT - 1) / self.depth.max(1);
+                writeln!(out, "    {n} [fillcolor={shade}];")?;
+            } else {
+                writeln!(out, "    {n};")?;
+            }
+        }
+        for &(n, _) in &self.nodes[1..] {
+            let next = if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+            writeln!(out, "    {n} -> {next};")?;
+        }
+        writeln!(out, "}}")
+    }
+}
+
+#[test]
+fn test_inverse_tree() {
+    let tree = InverseTree::new(8).unwrap();
+    let mut values: Vec<u64> = tree.nodes().iter().map(|&(n, _)| n).collect();
+    values.sort_unstable();
+    assert_eq!(
+        values,
+        [1, 2, 3, 4, 5, 6, 8, 10, 16, 20, 21, 32, 40, 42, 64, 128, 256]
+    );
+    for &(n, stopping_time) in tree.nodes() {
+        assert_eq!(crate::collatz_length(n), Ok(stopping_time));
+    }
+}
+
+#[test]
+fn test_inverse_tree_overflow() {
+    let err = InverseTree::new(64).unwrap_err();
+    assert_eq!(err, CollatzError::DoublingOverflow(1 << 63));
+    assert_eq!(
+        err.to_string(),
+        "2 * 9223372036854775808 overflows the integer type"
+    );
+}
+
+#[test]
+fn test_write_dot() {
+    let tree = InverseTree::new(3).unwrap();
+    let mut out = Vec::new();
+    tree.write_dot(&mut out, false).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "digraph collatz {\n    rankdir=BT;\n    1;\n    2;\n    4;\n    8;\n    2 -> 1;\n    4 -> 2;\n    8 -> 4;\n}\n"
+    );
+
+    let mut out = Vec::new();
+    tree.write_dot(&mut out, true).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    assert!(dot.contains("node [style=filled, colorscheme=ylorrd9];"));
+    assert!(dot.contains("    1 [fillcolor=1];"));
+    assert!(dot.contains("    8 [fillcolor=9];"));
+}
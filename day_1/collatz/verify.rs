@@ -0,0 +1,273 @@
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::{collatz_length, Chain, CollatzError};
+
+#[derive(Error, Debug)]
+pub enum VerifyError {
+    #[error(transparent)]
+    Collatz(#[from] CollatzError),
+    #[error("checkpoint I/O failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("corrupt checkpoint, line {line}: {reason}")]
+    Corrupt { line: usize, reason: String },
+    #[error("checkpoint is for {found:?}, not {expected:?}")]
+    RangeMismatch {
+        expected: Range<u64>,
+        found: Range<u64>,
+    },
+}
+
+/// The progress of a verification run, as stored in a checkpoint file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub range: Range<u64>,
+    /// The next value to verify; everything below has been verified.
+    pub next: u64,
+    /// Every start value whose sequence is longer than those of all smaller
+    /// start values in the range, in increasing order.
+    pub records: Vec<Chain>,
+}
+
+impl Checkpoint {
+    fn new(range: Range<u64>) -> Self {
+        Self {
+            next: range.start,
+            range,
+            records: Vec::new(),
+        }
+    }
+
+    /// Serialize as `key=value` lines, one `record=start,length` per record.
+    fn to_text(&self) -> String {
+        let mut text = format!(
+            "start={}\nend={}\nnext={}\n",
+            self.range.start, self.range.end, self.next
+        );
+        for record in &self.records {
+            text.push_str(&format!("record={},{}\n", record.start, record.length));
+        }
+        text
+    }
+
+    fn parse(text: &str) -> Result<Self, VerifyError> {
+        let (mut start, mut end, mut next) = (None, None, None);
+        let mut records = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let corrupt = |reason: &str| VerifyError::Corrupt {
+                line: i + 1,
+                reason: reason.to_string(),
+            };
+            let number = |value: &str| value.parse::<u64>().map_err(|_| corrupt("invalid number"));
+            let (key, value) = line.split_once('=').ok_or_else(|| corrupt("missing '='"))?;
+            match key {
+                "start" => start = Some(number(value)?),
+                "end" => end = Some(number(value)?),
+                "next" => next = Some(number(value)?),
+                "record" => {
+                    let (record_start, length) = value
+                        .split_once(',')
+                        .ok_or_else(|| corrupt("missing ','"))?;
+                    records.push(Chain {
+                        start: number(record_start)?,
+                        length: length.parse().map_err(|_| corrupt("invalid length"))?,
+                    });
+                }
+                _ => return Err(corrupt("unknown key")),
+            }
+        }
+        let missing = |key: &str| VerifyError::Corrupt {
+            line: text.lines().count(),
+            reason: format!("missing {key}"),
+        };
+        let range = start.ok_or_else(|| missing("start"))?..end.ok_or_else(|| missing("end"))?;
+        let next = next.ok_or_else(|| missing("next"))?;
+        if !(range.start..=range.end).contains(&next) {
+            return Err(VerifyError::Corrupt {
+                line: text.lines().count(),
+                reason: format!("next={next} is outside of {range:?}"),
+            });
+        }
+        Ok(Self {
+            range,
+            next,
+            records,
+        })
+    }
+}
+
+/// Verifies that every start value in a range reaches 1, keeping track of
+/// record holders and periodically saving its progress to a checkpoint file.
+pub struct Verifier {
+    path: PathBuf,
+    interval: u64,
+    state: Checkpoint,
+}
+
+impl Verifier {
+    /// Resume from the checkpoint at `path` if it exists, or start from the
+    /// beginning of `range` otherwise. A checkpoint is written every
+    /// `interval` verified values.
+    pub fn open(
+        range: Range<u64>,
+        path: impl AsRef<Path>,
+        interval: u64,
+    ) -> Result<Self, VerifyError> {
+        let path = path.as_ref().to_path_buf();
+        let state = match fs::read_to_string(&path) {
+            Ok(text) => {
+                let state = Checkpoint::parse(&text)?;
+                if state.range != range {
+                    return Err(VerifyError::RangeMismatch {
+                        expected: range,
+                        found: state.range,
+                    });
+                }
+                state
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Checkpoint::new(range),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self {
+            path,
+            interval: interval.max(1),
+            state,
+        })
+    }
+
+    pub fn state(&self) -> &Checkpoint {
+        &self.state
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state.next >= self.state.range.end
+    }
+
+    /// Verify values until `stop` (exclusive) or the end of the range.
+    ///
+    /// Progress since the last periodic checkpoint is not saved; call
+    /// `checkpoint` for that.
+    pub fn run_until(&mut self, stop: u64) -> Result<(), VerifyError> {
+        let stop = stop.min(self.state.range.end);
+        while self.state.next < stop {
+            let n = self.state.next;
+            let length = collatz_length(n)?;
+            if self.state.records.last().is_none_or(|r| length > r.length) {
+                self.state.records.push(Chain { start: n, length });
+            }
+            self.state.next += 1;
+            if (self.state.next - self.state.range.start).is_multiple_of(self.interval)
+                || self.is_done()
+            {
+                self.checkpoint()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify the rest of the range and return the record holders.
+    pub fn run(&mut self) -> Result<&[Chain], VerifyError> {
+        self.run_until(self.state.range.end)?;
+        Ok(&self.state.records)
+    }
+
+    /// Save the current progress. The file is replaced atomically, so an
+    /// interruption never leaves a half-written checkpoint behind.
+    pub fn checkpoint(&self) -> Result<(), VerifyError> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, self.state.to_text())?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn temp_checkpoint(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("collatz-{name}-{}.checkpoint", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_checkpoint_round_trip() {
+    let checkpoint = Checkpoint {
+        range: 1..100,
+        next: 10,
+        records: vec![
+            Chain {
+                start: 1,
+                length: 0,
+            },
+            Chain {
+                start: 2,
+                length: 1,
+            },
+            Chain {
+                start: 3,
+                length: 7,
+            },
+        ],
+    };
+    assert_eq!(
+        Checkpoint::parse(&checkpoint.to_text()).unwrap(),
+        checkpoint
+    );
+}
+
+#[test]
+fn test_corrupt_checkpoint() {
+    let err = Checkpoint::parse("start=1\nend=10\nnext=x\n").unwrap_err();
+    assert!(matches!(err, VerifyError::Corrupt { line: 3, .. }));
+    let err = Checkpoint::parse("start=1\nend=10\n").unwrap_err();
+    assert!(matches!(err, VerifyError::Corrupt { .. }));
+    let err = Checkpoint::parse("start=1\nend=10\nnext=20\n").unwrap_err();
+    assert!(matches!(err, VerifyError::Corrupt { .. }));
+}
+
+#[test]
+fn test_interrupted_run_matches_uninterrupted() {
+    let range = 1..20_000;
+    let path = temp_checkpoint("uninterrupted");
+    let expected = Verifier::open(range.clone(), &path, 1000)
+        .unwrap()
+        .run()
+        .unwrap()
+        .to_vec();
+    fs::remove_file(&path).unwrap();
+
+    // Stop between two checkpoints, as if the process had been killed.
+    let path = temp_checkpoint("interrupted");
+    let mut verifier = Verifier::open(range.clone(), &path, 1000).unwrap();
+    verifier.run_until(12_345).unwrap();
+    drop(verifier);
+
+    let mut verifier = Verifier::open(range.clone(), &path, 1000).unwrap();
+    assert_eq!(verifier.state().next, 12_001);
+    let records = verifier.run().unwrap().to_vec();
+    assert_eq!(records, expected);
+    assert_eq!(records.last().map(|r| r.start), Some(17_647));
+
+    // A finished checkpoint resumes as done.
+    let verifier = Verifier::open(range, &path, 1000).unwrap();
+    assert!(verifier.is_done());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_range_mismatch() {
+    let path = temp_checkpoint("mismatch");
+    Verifier::open(1..100, &path, 10)
+        .unwrap()
+        .run_until(50)
+        .unwrap();
+    assert!(matches!(
+        Verifier::open(1..200, &path, 10),
+        Err(VerifyError::RangeMismatch { .. })
+    ));
+    fs::remove_file(&path).unwrap();
+}
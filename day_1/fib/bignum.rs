@@ -0,0 +1,468 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Largest power of ten that fits into a single limb, used for printing.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+/// An arbitrary-precision unsigned integer.
+///
+/// The value is stored as little-endian base 2^32 limbs without trailing
+/// zero limbs, so zero is represented by an empty vector.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u32)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of significant bits, zero for the value zero.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() as u64 * 32 - u64::from(top.leading_zeros()),
+        }
+    }
+
+    /// Returns the value as a `u64` if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [lo] => Some(u64::from(*lo)),
+            [lo, hi] => Some(u64::from(*lo) | u64::from(*hi) << 32),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `u128` if it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &limb| acc << 32 | u128::from(limb)),
+        )
+    }
+
+    /// Subtracts `other`, returning `None` if the result would be negative.
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0u64;
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let b = u64::from(other.limbs.get(i).copied().unwrap_or(0)) + borrow;
+            let a = u64::from(a);
+            if a >= b {
+                limbs.push((a - b) as u32);
+                borrow = 0;
+            } else {
+                limbs.push((a + (1 << 32) - b) as u32);
+                borrow = 1;
+            }
+        }
+        Some(Self::normalized(limbs))
+    }
+
+    /// Divides in place by a small divisor and returns the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let cur = rem << 32 | u64::from(*limb);
+            *limb = (cur / u64::from(divisor)) as u32;
+            rem = cur % u64::from(divisor);
+        }
+        self.trim();
+        rem as u32
+    }
+
+    fn normalized(limbs: Vec<u32>) -> Self {
+        let mut n = Self { limbs };
+        n.trim();
+        n
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        Self::normalized(vec![value])
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::normalized(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        Self::normalized((0..4).map(|i| (value >> (32 * i)) as u32).collect())
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u64;
+        for (i, &a) in long.limbs.iter().enumerate() {
+            let sum = u64::from(a) + u64::from(short.limbs.get(i).copied().unwrap_or(0)) + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        BigUint::normalized(limbs)
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        &self + &other
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if `other` is larger than `self`, like unsigned primitives do.
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Sub for BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: BigUint) -> BigUint {
+        &self - &other
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                // a * b + limb + carry never exceeds u64::MAX.
+                let cur = u64::from(a) * u64::from(b) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::normalized(limbs)
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+        // Peel off chunks of nine decimal digits, least significant first.
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(DECIMAL_BASE));
+        }
+        let mut digits = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{chunk:0width$}", width = DECIMAL_DIGITS));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+/// An arbitrary-precision signed integer, as a sign and a magnitude.
+///
+/// Zero is never negative, so that every value has one representation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The absolute value.
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// Returns the value as an `i64` if it fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = self.magnitude.to_u64()?;
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// Returns the value as a `BigUint` if it is not negative.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        (!self.negative).then(|| self.magnitude.clone())
+    }
+
+    fn new(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::new(false, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::new(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, &self.magnitude + &other.magnitude);
+        }
+        // Opposite signs: the larger magnitude decides the sign.
+        match self.magnitude.checked_sub(&other.magnitude) {
+            Some(difference) => BigInt::new(self.negative, difference),
+            None => BigInt::new(other.negative, &other.magnitude - &self.magnitude),
+        }
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        &self + &other
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: BigInt) -> BigInt {
+        &self - &other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> BigInt {
+        &self * &other
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+#[test]
+fn test_add_carries_across_limbs() {
+    let a = BigUint::from(u64::MAX);
+    let sum = &a + &BigUint::one();
+    assert_eq!(sum, BigUint::from(1u128 << 64));
+    assert_eq!(sum.to_u64(), None);
+    assert_eq!(sum.to_u128(), Some(1 << 64));
+}
+
+#[test]
+fn test_sub() {
+    let a = BigUint::from(1u128 << 64);
+    assert_eq!(&a - &BigUint::one(), BigUint::from(u64::MAX));
+    assert_eq!(BigUint::one().checked_sub(&a), None);
+    assert!((&a - &a).is_zero());
+}
+
+#[test]
+fn test_mul_matches_u128() {
+    let a = 0xdead_beef_cafe_babe_u64;
+    let b = 0x1234_5678_9abc_def0_u64;
+    let product = BigUint::from(a) * BigUint::from(b);
+    assert_eq!(product.to_u128(), Some(u128::from(a) * u128::from(b)));
+    assert!((BigUint::zero() * BigUint::from(a)).is_zero());
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(BigUint::zero().to_string(), "0");
+    assert_eq!(BigUint::from(1_000_000_000u32).to_string(), "1000000000");
+    assert_eq!(
+        BigUint::from(u128::MAX).to_string(),
+        "340282366920938463463374607431768211455"
+    );
+    assert_eq!(format!("{:>5}", BigUint::from(42u32)), "   42");
+}
+
+#[test]
+fn test_ordering_and_bits() {
+    assert!(BigUint::from(1u128 << 64) > BigUint::from(u64::MAX));
+    assert_eq!(BigUint::zero().bits(), 0);
+    assert_eq!(BigUint::from(1u128 << 64).bits(), 65);
+}
+
+#[test]
+fn test_signed_arithmetic() {
+    let a = BigInt::from(-7);
+    let b = BigInt::from(3);
+    assert_eq!(&a + &b, BigInt::from(-4));
+    assert_eq!(&b + &a, BigInt::from(-4));
+    assert_eq!(&b - &a, BigInt::from(10));
+    assert_eq!(&a * &b, BigInt::from(-21));
+    assert_eq!(&a * &a, BigInt::from(49));
+    // Zero has no sign, whichever way it is reached.
+    assert_eq!(&a - &a, BigInt::zero());
+    assert!(!(&a - &a).is_negative());
+    assert_eq!(-BigInt::zero(), BigInt::zero());
+    assert!(a < b && BigInt::from(-8) < a);
+
+    let big = BigInt::from(BigUint::from(1u128 << 64));
+    assert_eq!((-&big).to_string(), "-18446744073709551616");
+    assert_eq!(big.to_i64(), None);
+    assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+    assert_eq!(BigInt::from(i64::MIN).magnitude().to_u64(), Some(1 << 63));
+    assert_eq!(a.to_biguint(), None);
+    assert_eq!(format!("{:>4}", a), "  -7");
+}
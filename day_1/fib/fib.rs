@@ -1,21 +1,259 @@
-fn fib(n: u32) -> u32 {
-    if n <= 2 {
-        1
-    } else {
-        fib(n - 1) + fib(n - 2)
+use fib::{BigInt, LinearRecurrence};
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::process::ExitCode;
+use thiserror::Error;
+
+const USAGE: &str = "\
+Usage: fib [--format plain|csv|json] [SEQUENCE] [INDEX | START..END | START..=END]
+
+SEQUENCE is one of fibonacci (default), lucas, pell, tribonacci or padovan.
+INDEX defaults to 20. Indices start at 0 with fib(0) = 0.";
+
+#[derive(Error, Debug)]
+enum CliError {
+    #[error("unknown option {0:?}")]
+    UnknownOption(String),
+    #[error("missing value for {0}")]
+    MissingValue(&'static str),
+    #[error("unknown format {0:?}, expected plain, csv or json")]
+    UnknownFormat(String),
+    #[error("invalid index or range {0:?}")]
+    InvalidIndex(String),
+    #[error("range {0:?} is empty")]
+    EmptyRange(String),
+    #[error("unexpected argument {0:?}")]
+    UnexpectedArgument(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl CliError {
+    /// Usage errors exit with 2, like most command-line tools.
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            CliError::Io(_) => ExitCode::FAILURE,
+            _ => ExitCode::from(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Plain,
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+struct Options {
+    format: Format,
+    sequence: &'static str,
+    indices: RangeInclusive<u64>,
+}
+
+const SEQUENCES: [&str; 5] = ["fibonacci", "lucas", "pell", "tribonacci", "padovan"];
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+    let mut format = Format::Plain;
+    let mut sequence = None;
+    let mut indices = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" | "-f" => {
+                let value = args.next().ok_or(CliError::MissingValue("--format"))?;
+                format = parse_format(&value)?;
+            }
+            _ if arg.starts_with("--format=") => format = parse_format(&arg["--format=".len()..])?,
+            _ if arg.starts_with('-') => return Err(CliError::UnknownOption(arg)),
+            _ if sequence.is_none() && indices.is_none() && SEQUENCES.contains(&arg.as_str()) => {
+                sequence = SEQUENCES.iter().copied().find(|s| *s == arg);
+            }
+            _ if indices.is_none() => indices = Some(parse_indices(&arg)?),
+            _ => return Err(CliError::UnexpectedArgument(arg)),
+        }
+    }
+    Ok(Options {
+        format,
+        sequence: sequence.unwrap_or("fibonacci"),
+        indices: indices.unwrap_or(20..=20),
+    })
+}
+
+fn parse_format(value: &str) -> Result<Format, CliError> {
+    match value {
+        "plain" => Ok(Format::Plain),
+        "csv" => Ok(Format::Csv),
+        "json" => Ok(Format::Json),
+        _ => Err(CliError::UnknownFormat(value.to_string())),
+    }
+}
+
+/// Parse `N`, `A..B` (end exclusive) or `A..=B` (end inclusive).
+fn parse_indices(arg: &str) -> Result<RangeInclusive<u64>, CliError> {
+    let invalid = || CliError::InvalidIndex(arg.to_string());
+    let range = match arg.split_once("..") {
+        None => {
+            let n = arg.parse().map_err(|_| invalid())?;
+            n..=n
+        }
+        Some((start, end)) => {
+            let start = start.parse().map_err(|_| invalid())?;
+            match end.strip_prefix('=') {
+                Some(end) => start..=end.parse().map_err(|_| invalid())?,
+                None => {
+                    let end: u64 = end.parse().map_err(|_| invalid())?;
+                    let Some(last) = end.checked_sub(1) else {
+                        return Err(CliError::EmptyRange(arg.to_string()));
+                    };
+                    start..=last
+                }
+            }
+        }
+    };
+    if range.is_empty() {
+        return Err(CliError::EmptyRange(arg.to_string()));
     }
+    Ok(range)
 }
 
-fn main() {
-    let n = 20;
-    println!("fib(n) = {}", fib(n));
+/// Calculate the requested terms, jumping straight to the first index.
+/// Fails if the sequence cannot be started that close to `u64::MAX`.
+fn terms(sequence: &str, indices: &RangeInclusive<u64>) -> Result<Vec<(u64, BigInt)>, CliError> {
+    let recurrence = LinearRecurrence::by_name(sequence).expect("sequence names are validated");
+    let start = *indices.start();
+    let terms = recurrence
+        .iter_from(start)
+        .ok_or_else(|| CliError::InvalidIndex(start.to_string()))?;
+    Ok(indices.clone().zip(terms).collect())
+}
+
+fn write_terms(out: &mut impl Write, options: &Options) -> Result<(), CliError> {
+    let terms = terms(options.sequence, &options.indices)?;
+    match options.format {
+        Format::Plain => {
+            let name = match options.sequence {
+                "fibonacci" => "fib",
+                name => name,
+            };
+            for (n, value) in &terms {
+                writeln!(out, "{name}({n}) = {value}")?;
+            }
+        }
+        Format::Csv => {
+            writeln!(out, "n,value")?;
+            for (n, value) in &terms {
+                writeln!(out, "{n},{value}")?;
+            }
+        }
+        Format::Json => {
+            // Values are strings, as most JSON parsers cannot hold big integers.
+            let items: Vec<String> = terms
+                .iter()
+                .map(|(n, value)| format!("{{\"n\":{n},\"value\":\"{value}\"}}"))
+                .collect();
+            writeln!(
+                out,
+                "{{\"sequence\":\"{}\",\"terms\":[{}]}}",
+                options.sequence,
+                items.join(",")
+            )?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn run(args: impl IntoIterator<Item = String>) -> Result<(), CliError> {
+    let options = parse_args(args)?;
+    write_terms(&mut io::stdout().lock(), &options)?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("fib: {err}");
+            if !matches!(err, CliError::Io(_)) {
+                eprintln!("{USAGE}");
+            }
+            err.exit_code()
+        }
+    }
+}
+
+#[cfg(test)]
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn test_parse_indices() {
+    assert_eq!(parse_indices("7").unwrap(), 7..=7);
+    assert_eq!(parse_indices("3..6").unwrap(), 3..=5);
+    assert_eq!(parse_indices("3..=6").unwrap(), 3..=6);
+    assert!(matches!(
+        parse_indices("6..3"),
+        Err(CliError::EmptyRange(_))
+    ));
+    assert!(matches!(
+        parse_indices("0..0"),
+        Err(CliError::EmptyRange(_))
+    ));
+    assert!(matches!(
+        parse_indices("x..3"),
+        Err(CliError::InvalidIndex(_))
+    ));
+    assert!(matches!(
+        parse_indices("-1"),
+        Err(CliError::InvalidIndex(_))
+    ));
+}
+
+#[test]
+fn test_parse_args() {
+    let options = parse_args(args(&[])).unwrap();
+    assert_eq!(options.sequence, "fibonacci");
+    assert_eq!(options.indices, 20..=20);
+    assert_eq!(options.format, Format::Plain);
+
+    let options = parse_args(args(&["--format", "json", "pell", "0..4"])).unwrap();
+    assert_eq!(options.sequence, "pell");
+    assert_eq!(options.indices, 0..=3);
+    assert_eq!(options.format, Format::Json);
+
+    assert!(matches!(
+        parse_args(args(&["--format=xml"])),
+        Err(CliError::UnknownFormat(_))
+    ));
+    assert!(matches!(
+        parse_args(args(&["--format"])),
+        Err(CliError::MissingValue(_))
+    ));
+    assert!(matches!(
+        parse_args(args(&["1", "2"])),
+        Err(CliError::UnexpectedArgument(_))
+    ));
+    assert!(matches!(
+        parse_args(args(&["--verbose"])),
+        Err(CliError::UnknownOption(_))
+    ));
 }
 
 #[test]
-fn test_fib() {
-    assert_eq!(fib(0), 1);
-    assert_eq!(fib(1), 1);
-    assert_eq!(fib(2), 1);
-    assert_eq!(fib(10), 55);
-    assert_eq!(fib(30), 832040);
+fn test_write_terms() {
+    let options = parse_args(args(&["--format", "csv", "lucas", "0..=3"])).unwrap();
+    let mut out = Vec::new();
+    write_terms(&mut out, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "n,value\n0,2\n1,1\n2,3\n3,4\n"
+    );
 }
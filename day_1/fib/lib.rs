@@ -0,0 +1,93 @@
+use thiserror::Error;
+
+mod bignum;
+mod modular;
+mod recurrence;
+mod zeckendorf;
+
+pub use bignum::{BigInt, BigUint};
+pub use modular::{fib_mod, pisano_period};
+pub use recurrence::{LinearRecurrence, RecurrenceError, Terms};
+pub use zeckendorf::{decode, encode, zeckendorf, FibReader, FibWriter};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FibError {
+    #[error("fib({0}) does not fit into a u32")]
+    Overflow(u32),
+}
+
+/// Calculate the n'th Fibonacci number, returning an error instead of
+/// overflowing.
+///
+/// Like the original exercise, the sequence starts with `fib(0) == 1`. Use
+/// `fib_big` for indices beyond 47.
+pub fn fib(n: u32) -> Result<u32, FibError> {
+    let (mut a, mut b) = (1u32, 1u32);
+    for _ in 2..n {
+        (a, b) = (b, a.checked_add(b).ok_or(FibError::Overflow(n))?);
+    }
+    Ok(b)
+}
+
+/// Calculate the n'th Fibonacci number with `F(0) = 0` and `F(1) = 1`.
+///
+/// Uses the fast-doubling identities
+/// `F(2k) = F(k) * (2 * F(k + 1) - F(k))` and
+/// `F(2k + 1) = F(k)^2 + F(k + 1)^2`,
+/// so only O(log n) big-number multiplications are needed.
+pub fn fib_big(n: u64) -> BigUint {
+    // Invariant: (a, b) == (F(k), F(k + 1)) for the prefix k of n's bits.
+    let mut a = BigUint::zero();
+    let mut b = BigUint::one();
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let two_b = &b + &b;
+        let c = &a * &(&two_b - &a);
+        let d = &(&a * &a) + &(&b * &b);
+        if n >> bit & 1 == 1 {
+            b = &c + &d;
+            a = d;
+        } else {
+            a = c;
+            b = d;
+        }
+    }
+    a
+}
+
+#[test]
+fn test_fib() {
+    assert_eq!(fib(0), Ok(1));
+    assert_eq!(fib(1), Ok(1));
+    assert_eq!(fib(2), Ok(1));
+    assert_eq!(fib(10), Ok(55));
+    assert_eq!(fib(30), Ok(832040));
+}
+
+#[test]
+fn test_fib_overflow() {
+    assert_eq!(fib(47), Ok(2971215073));
+    assert_eq!(fib(48), Err(FibError::Overflow(48)));
+    assert_eq!(fib(u32::MAX), Err(FibError::Overflow(u32::MAX)));
+}
+
+#[test]
+fn test_fib_big_matches_iteration() {
+    let (mut a, mut b) = (0u128, 1u128);
+    for n in 0..180 {
+        assert_eq!(fib_big(n).to_u128(), Some(a), "fib_big({n})");
+        (a, b) = (b, a + b);
+    }
+}
+
+#[test]
+fn test_fib_big_large() {
+    assert_eq!(
+        fib_big(300).to_string(),
+        "222232244629420445529739893461909967206666939096499764990979600"
+    );
+    let f = fib_big(100_000);
+    let digits = f.to_string();
+    assert_eq!(digits.len(), 20899);
+    assert!(digits.starts_with("259740693472217241661550340212759154148804853865176965847247"));
+    assert!(digits.ends_with("289236362349895374653428746875"));
+}
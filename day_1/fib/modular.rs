@@ -0,0 +1,301 @@
+/// Calculate `F(n) mod m` with fast doubling.
+///
+/// All products are done in 128-bit arithmetic, so any `u64` modulus works.
+/// Panics if `m` is zero, like the `%` operator.
+pub fn fib_mod(n: u128, m: u64) -> u64 {
+    assert!(
+        m != 0,
+        "attempt to calculate fib_mod with a modulus of zero"
+    );
+    let m = u128::from(m);
+    // Invariant: (a, b) == (F(k) mod m, F(k + 1) mod m).
+    let mut a = 0u128;
+    let mut b = 1 % m;
+    for bit in (0..u128::BITS - n.leading_zeros()).rev() {
+        let c = a * ((2 * b + m - a) % m) % m;
+        let d = (a * a % m + b * b % m) % m;
+        if n >> bit & 1 == 1 {
+            (a, b) = (d, (c + d) % m);
+        } else {
+            (a, b) = (c, d);
+        }
+    }
+    a as u64
+}
+
+/// Calculate the Pisano period, the period of the Fibonacci sequence modulo
+/// `m`.
+///
+/// The period of every prime power p^e in the factorization of `m` is
+/// searched among the divisors of a known multiple, and the results are
+/// combined via LCM. The result can exceed `u64::MAX`, as the period can be
+/// as large as 6m. Panics if `m` is zero.
+pub fn pisano_period(m: u64) -> u128 {
+    assert!(m != 0, "the Pisano period of zero is undefined");
+    factorize(m)
+        .into_iter()
+        .map(|(p, e)| prime_power_period(p, e))
+        .fold(1, lcm)
+}
+
+/// Whether `F(n) ≡ 0` and `F(n + 1) ≡ 1 (mod m)`, i.e. n is a multiple of
+/// the Pisano period.
+fn is_period(n: u128, m: u64) -> bool {
+    fib_mod(n, m) == 0 && fib_mod(n + 1, m) == 1 % m
+}
+
+fn prime_power_period(p: u64, e: u32) -> u128 {
+    let pe = p.pow(e);
+    let lift = u128::from(p).pow(e - 1);
+    // π(p^e) divides p^(e-1) π(p), and π(p) divides a value known from p mod 5.
+    let (base, mut factors) = match (p, p % 5) {
+        (2, _) => (3, vec![(3, 1)]),
+        (5, _) => (20, vec![(2, 2), (5, 1)]),
+        (_, 1 | 4) => (u128::from(p - 1), factorize(p - 1)),
+        _ => {
+            let mut factors = factorize(p + 1);
+            match factors.iter_mut().find(|(q, _)| *q == 2) {
+                Some((_, e)) => *e += 1,
+                None => factors.push((2, 1)),
+            }
+            (2 * u128::from(p + 1), factors)
+        }
+    };
+    match factors.iter_mut().find(|(q, _)| *q == p) {
+        Some((_, exponent)) => *exponent += e - 1,
+        None if e > 1 => factors.push((p, e - 1)),
+        None => {}
+    }
+
+    // Strip prime factors from the known multiple while it remains a period.
+    let mut period = base * lift;
+    for (q, exponent) in factors {
+        for _ in 0..exponent {
+            if period % u128::from(q) == 0 && is_period(period / u128::from(q), pe) {
+                period /= u128::from(q);
+            } else {
+                break;
+            }
+        }
+    }
+    period
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn lcm(a: u128, b: u128) -> u128 {
+    a / gcd(a, b) * b
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Deterministic Miller-Rabin; these bases cover every 64-bit integer.
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// The pseudorandom map `x -> x^2 + c (mod n)` of Pollard's rho. The sum
+/// is taken in u128, as it overflows a u64 for `n` close to 2^64.
+fn rho_step(x: u64, c: u64, n: u64) -> u64 {
+    ((u128::from(mul_mod(x, x, n)) + u128::from(c)) % u128::from(n)) as u64
+}
+
+/// Find a non-trivial factor of the odd composite `n` with Pollard's rho.
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| rho_step(x, c, n);
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(u128::from(x.abs_diff(y)), u128::from(n)) as u64;
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+/// Prime factorization as sorted (prime, exponent) pairs.
+fn factorize(n: u64) -> Vec<(u64, u32)> {
+    fn split(n: u64, primes: &mut Vec<u64>) {
+        if n == 1 {
+            return;
+        }
+        if is_prime(n) {
+            primes.push(n);
+            return;
+        }
+        let d = pollard_rho(n);
+        split(d, primes);
+        split(n / d, primes);
+    }
+
+    let mut primes = Vec::new();
+    let mut n = n;
+    for p in [2, 3, 5, 7, 11, 13] {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    split(n, &mut primes);
+    primes.sort_unstable();
+
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+#[test]
+fn test_fib_mod_matches_fib_big() {
+    for m in [1, 2, 7, 10, 1_000_000_007, u64::MAX] {
+        for n in 0..180 {
+            let expected = crate::fib_big(n).to_u128().unwrap() % u128::from(m);
+            assert_eq!(
+                u128::from(fib_mod(u128::from(n), m)),
+                expected,
+                "F({n}) mod {m}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_fib_mod_huge_index() {
+    let m = 1_000_000_007;
+    let n = u128::MAX - 5;
+    let period = pisano_period(m);
+    assert_eq!(fib_mod(n, m), fib_mod(n % period, m));
+}
+
+#[test]
+fn test_pisano_period_known_values() {
+    let known = [
+        (1, 1),
+        (2, 3),
+        (3, 8),
+        (4, 6),
+        (5, 20),
+        (7, 16),
+        (10, 60),
+        (11, 10),
+        (25, 100),
+        (100, 300),
+        (1000, 1500),
+        (1_000_000_007, 2_000_000_016),
+    ];
+    for (m, period) in known {
+        assert_eq!(pisano_period(m), period, "π({m})");
+    }
+}
+
+#[test]
+fn test_pisano_period_matches_brute_force() {
+    for m in 1..=300u64 {
+        let (mut a, mut b, mut period) = (0, 1 % m, 0u128);
+        loop {
+            (a, b) = (b, (a + b) % m);
+            period += 1;
+            if a == 0 && b == 1 % m {
+                break;
+            }
+        }
+        assert_eq!(pisano_period(m), period, "π({m})");
+    }
+}
+
+#[test]
+fn test_pisano_period_large_moduli() {
+    for m in [
+        1_000_000_007,
+        1_000_000_000_000_000_000,
+        (1 << 61) - 1,
+        4_294_967_291 * 4_294_967_279,
+        u64::MAX,
+    ] {
+        let period = pisano_period(m);
+        assert!(is_period(period, m), "π({m}) = {period}");
+        assert!(period <= 6 * u128::from(m));
+    }
+}
+
+#[test]
+fn test_rho_step_near_u64_max() {
+    // x^2 = -9 (mod 2^64 - 1), so adding 10 steps past 2^64.
+    let x = 3_397_880_441_062_532_781;
+    assert_eq!(mul_mod(x, x, u64::MAX), u64::MAX - 9);
+    assert_eq!(rho_step(x, 10, u64::MAX), 1);
+}
+
+#[test]
+fn test_factorize() {
+    assert_eq!(factorize(1), []);
+    assert_eq!(factorize(360), [(2, 3), (3, 2), (5, 1)]);
+    assert_eq!(
+        factorize(u64::MAX),
+        [
+            (3, 1),
+            (5, 1),
+            (17, 1),
+            (257, 1),
+            (641, 1),
+            (65537, 1),
+            (6700417, 1)
+        ]
+    );
+    assert_eq!(
+        factorize(4_294_967_291 * 4_294_967_279),
+        [(4_294_967_279, 1), (4_294_967_291, 1)]
+    );
+}
@@ -0,0 +1,291 @@
+use std::collections::VecDeque;
+use thiserror::Error;
+
+use crate::BigInt;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RecurrenceError {
+    #[error("a recurrence needs at least one coefficient")]
+    Empty,
+    #[error("expected {coefficients} seed values, got {seeds}")]
+    SeedMismatch { coefficients: usize, seeds: usize },
+}
+
+/// A homogeneous linear recurrence of order k with integer coefficients:
+///
+/// `a(n) = c[0] * a(n - 1) + c[1] * a(n - 2) + ... + c[k - 1] * a(n - k)`
+///
+/// The first k terms `a(0), ..., a(k - 1)` are given as seed values. Both
+/// may be negative, as in `a(n) = 3 * a(n - 1) - a(n - 2)`, so the terms
+/// are signed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    coefficients: Vec<BigInt>,
+    seeds: Vec<BigInt>,
+}
+
+impl LinearRecurrence {
+    pub fn new(coefficients: &[i64], seeds: &[i64]) -> Result<Self, RecurrenceError> {
+        if coefficients.is_empty() {
+            return Err(RecurrenceError::Empty);
+        }
+        if coefficients.len() != seeds.len() {
+            return Err(RecurrenceError::SeedMismatch {
+                coefficients: coefficients.len(),
+                seeds: seeds.len(),
+            });
+        }
+        Ok(Self {
+            coefficients: coefficients.iter().map(|&c| BigInt::from(c)).collect(),
+            seeds: seeds.iter().map(|&s| BigInt::from(s)).collect(),
+        })
+    }
+
+    /// 0, 1, 1, 2, 3, 5, 8, ...
+    pub fn fibonacci() -> Self {
+        Self::new(&[1, 1], &[0, 1]).unwrap()
+    }
+
+    /// 2, 1, 3, 4, 7, 11, 18, ...
+    pub fn lucas() -> Self {
+        Self::new(&[1, 1], &[2, 1]).unwrap()
+    }
+
+    /// 0, 1, 2, 5, 12, 29, 70, ...
+    pub fn pell() -> Self {
+        Self::new(&[2, 1], &[0, 1]).unwrap()
+    }
+
+    /// 0, 0, 1, 1, 2, 4, 7, 13, ...
+    pub fn tribonacci() -> Self {
+        Self::new(&[1, 1, 1], &[0, 0, 1]).unwrap()
+    }
+
+    /// 1, 1, 1, 2, 2, 3, 4, 5, 7, ...
+    pub fn padovan() -> Self {
+        Self::new(&[0, 1, 1], &[1, 1, 1]).unwrap()
+    }
+
+    /// Look up one of the named sequences above.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "fibonacci" => Some(Self::fibonacci()),
+            "lucas" => Some(Self::lucas()),
+            "pell" => Some(Self::pell()),
+            "tribonacci" => Some(Self::tribonacci()),
+            "padovan" => Some(Self::padovan()),
+            _ => None,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Iterate over all terms, starting at `a(0)`.
+    pub fn iter(&self) -> Terms<'_> {
+        Terms {
+            coefficients: &self.coefficients,
+            window: self.seeds.iter().cloned().collect(),
+        }
+    }
+
+    /// Iterate over all terms, starting at `a(start)`. The first k terms are
+    /// found with `nth_term`, so a large `start` is cheap.
+    ///
+    /// Returns `None` if the indices of those k terms do not all fit in a
+    /// `u64`.
+    pub fn iter_from(&self, start: u64) -> Option<Terms<'_>> {
+        let end = start.checked_add(self.order() as u64 - 1)?;
+        Some(Terms {
+            coefficients: &self.coefficients,
+            window: (start..=end).map(|n| self.nth_term(n)).collect(),
+        })
+    }
+
+    /// Calculate `a(n)` directly by raising the companion matrix to the
+    /// (n - k + 1)'th power, which takes O(k^3 log n) multiplications.
+    pub fn nth_term(&self, n: u64) -> BigInt {
+        let k = self.order();
+        if n < k as u64 {
+            return self.seeds[n as usize].clone();
+        }
+        let power = companion_matrix(&self.coefficients).pow(n - k as u64 + 1);
+        // The state vector is (a(k - 1), ..., a(0)), newest term first.
+        power.rows[0]
+            .iter()
+            .zip(self.seeds.iter().rev())
+            .fold(BigInt::zero(), |acc, (m, s)| &acc + &(m * s))
+    }
+}
+
+/// An infinite iterator over the terms of a `LinearRecurrence`.
+pub struct Terms<'a> {
+    coefficients: &'a [BigInt],
+    /// The last k terms, oldest first.
+    window: VecDeque<BigInt>,
+}
+
+impl Iterator for Terms<'_> {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .coefficients
+            .iter()
+            .zip(self.window.iter().rev())
+            .fold(BigInt::zero(), |acc, (c, a)| &acc + &(c * a));
+        self.window.push_back(next);
+        self.window.pop_front()
+    }
+}
+
+/// A square matrix of big integers, just enough for exponentiation.
+#[derive(Debug, Clone)]
+struct SquareMatrix {
+    rows: Vec<Vec<BigInt>>,
+}
+
+/// The k x k matrix mapping (a(n - 1), ..., a(n - k)) to (a(n), ..., a(n - k + 1)).
+fn companion_matrix(coefficients: &[BigInt]) -> SquareMatrix {
+    let k = coefficients.len();
+    let mut m = SquareMatrix::zero(k);
+    m.rows[0] = coefficients.to_vec();
+    for i in 1..k {
+        m.rows[i][i - 1] = BigInt::one();
+    }
+    m
+}
+
+impl SquareMatrix {
+    fn zero(size: usize) -> Self {
+        Self {
+            rows: vec![vec![BigInt::zero(); size]; size],
+        }
+    }
+
+    fn identity(size: usize) -> Self {
+        let mut m = Self::zero(size);
+        for i in 0..size {
+            m.rows[i][i] = BigInt::one();
+        }
+        m
+    }
+
+    fn mul(&self, other: &SquareMatrix) -> SquareMatrix {
+        let size = self.rows.len();
+        let mut product = Self::zero(size);
+        for i in 0..size {
+            for j in 0..size {
+                product.rows[i][j] = (0..size).fold(BigInt::zero(), |acc, l| {
+                    &acc + &(&self.rows[i][l] * &other.rows[l][j])
+                });
+            }
+        }
+        product
+    }
+
+    /// Exponentiation by squaring.
+    fn pow(&self, mut exponent: u64) -> SquareMatrix {
+        let mut result = Self::identity(self.rows.len());
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+fn first_terms(recurrence: &LinearRecurrence, count: usize) -> Vec<i64> {
+    recurrence
+        .iter()
+        .take(count)
+        .map(|t| t.to_i64().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_named_sequences() {
+    let fibonacci = LinearRecurrence::fibonacci();
+    assert_eq!(first_terms(&fibonacci, 8), [0, 1, 1, 2, 3, 5, 8, 13]);
+    let lucas = LinearRecurrence::lucas();
+    assert_eq!(first_terms(&lucas, 8), [2, 1, 3, 4, 7, 11, 18, 29]);
+    let pell = LinearRecurrence::pell();
+    assert_eq!(first_terms(&pell, 8), [0, 1, 2, 5, 12, 29, 70, 169]);
+    let tribonacci = LinearRecurrence::tribonacci();
+    assert_eq!(first_terms(&tribonacci, 8), [0, 0, 1, 1, 2, 4, 7, 13]);
+    let padovan = LinearRecurrence::padovan();
+    assert_eq!(first_terms(&padovan, 10), [1, 1, 1, 2, 2, 3, 4, 5, 7, 9]);
+}
+
+#[test]
+fn test_nth_term_matches_iterator() {
+    for name in ["fibonacci", "lucas", "pell", "tribonacci", "padovan"] {
+        let recurrence = LinearRecurrence::by_name(name).unwrap();
+        for (n, term) in recurrence.iter().take(120).enumerate() {
+            assert_eq!(recurrence.nth_term(n as u64), term, "{name}({n})");
+        }
+    }
+}
+
+#[test]
+fn test_iter_from() {
+    let tribonacci = LinearRecurrence::tribonacci();
+    let expected: Vec<BigInt> = tribonacci.iter().skip(500).take(20).collect();
+    let actual: Vec<BigInt> = tribonacci.iter_from(500).unwrap().take(20).collect();
+    assert_eq!(actual, expected);
+    assert!(tribonacci.iter_from(u64::MAX - 1).is_none());
+    // 1, 0, 0, ... is cheap to evaluate all the way up to the last index.
+    let impulse = LinearRecurrence::new(&[0], &[1]).unwrap();
+    let last: Vec<BigInt> = impulse.iter_from(u64::MAX).unwrap().take(2).collect();
+    assert_eq!(last, [BigInt::zero(), BigInt::zero()]);
+}
+
+#[test]
+fn test_nth_term_matches_fib_big() {
+    let fibonacci = LinearRecurrence::fibonacci();
+    assert_eq!(
+        fibonacci.nth_term(5_000),
+        BigInt::from(crate::fib_big(5_000))
+    );
+}
+
+#[test]
+fn test_negative_coefficients() {
+    // Every other Fibonacci number: F(2n + 2) = 3 F(2n) - F(2n - 2).
+    let even_fibonacci = LinearRecurrence::new(&[3, -1], &[0, 1]).unwrap();
+    assert_eq!(first_terms(&even_fibonacci, 6), [0, 1, 3, 8, 21, 55]);
+    assert_eq!(
+        even_fibonacci.nth_term(1_000),
+        BigInt::from(crate::fib_big(2_000))
+    );
+    // a(n) = -a(n - 1) + 2 a(n - 2) has the closed form (1 - (-2)^n) / 3,
+    // which alternates in sign.
+    let alternating = LinearRecurrence::new(&[-1, 2], &[0, 1]).unwrap();
+    assert_eq!(first_terms(&alternating, 7), [0, 1, -1, 3, -5, 11, -21]);
+    for (n, term) in alternating.iter().take(80).enumerate() {
+        assert_eq!(alternating.nth_term(n as u64), term);
+    }
+    let expected = BigInt::from(crate::BigUint::from(((1u128 << 100) - 1) / 3));
+    assert_eq!(alternating.nth_term(100), -expected);
+}
+
+#[test]
+fn test_invalid_recurrence() {
+    assert_eq!(LinearRecurrence::new(&[], &[]), Err(RecurrenceError::Empty));
+    assert_eq!(
+        LinearRecurrence::new(&[1, 1], &[1]),
+        Err(RecurrenceError::SeedMismatch {
+            coefficients: 2,
+            seeds: 1
+        })
+    );
+    assert!(LinearRecurrence::by_name("catalan").is_none());
+}
@@ -0,0 +1,86 @@
+use std::process::{Command, Output};
+
+fn fib(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fib"))
+        .args(args)
+        .output()
+        .expect("failed to run fib")
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_default_index() {
+    let output = fib(&[]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "fib(20) = 6765\n");
+}
+
+#[test]
+fn test_single_index() {
+    let output = fib(&["90"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "fib(90) = 2880067194370816120\n");
+}
+
+#[test]
+fn test_large_index_does_not_overflow() {
+    let output = fib(&["1000"]);
+    assert!(output.status.success());
+    let value = stdout(&output).trim().strip_prefix("fib(1000) = ").unwrap();
+    assert_eq!(value.len(), 209);
+    assert!(value.starts_with("43466557686937456435"));
+}
+
+#[test]
+fn test_range_csv() {
+    let output = fib(&["--format", "csv", "5..10"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "n,value\n5,5\n6,8\n7,13\n8,21\n9,34\n");
+}
+
+#[test]
+fn test_sequence_json() {
+    let output = fib(&["--format=json", "pell", "0..=3"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "{\"sequence\":\"pell\",\"terms\":[{\"n\":0,\"value\":\"0\"},{\"n\":1,\"value\":\"1\"},{\"n\":2,\"value\":\"2\"},{\"n\":3,\"value\":\"5\"}]}\n"
+    );
+}
+
+#[test]
+fn test_sequence_name_only() {
+    let output = fib(&["padovan"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "padovan(20) = 200\n");
+}
+
+#[test]
+fn test_invalid_arguments() {
+    for args in [
+        &["abc"][..],
+        &["10..5"],
+        &["--format", "xml"],
+        &["--format"],
+        &["--verbose"],
+        &["1", "2"],
+        // The recurrence needs the terms at the following indices as well.
+        &["18446744073709551615"],
+        &["tribonacci", "18446744073709551614"],
+    ] {
+        let output = fib(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(output.stdout.is_empty());
+        assert!(!output.stderr.is_empty());
+    }
+}
+
+#[test]
+fn test_help() {
+    let output = fib(&["--help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Usage: fib"));
+}
@@ -0,0 +1,267 @@
+use std::io::{self, Read, Write};
+
+/// F(2), F(3), ..., F(93): every Fibonacci number needed to represent the
+/// values 1 to 2^64.
+const FIBS: [u128; 92] = {
+    let mut fibs = [0; 92];
+    let (mut a, mut b) = (1, 2);
+    let mut i = 0;
+    while i < fibs.len() {
+        fibs[i] = a;
+        (a, b) = (b, a + b);
+        i += 1;
+    }
+    fibs
+};
+
+/// Zeckendorf representation of `n` as a bit set: bit i stands for `FIBS[i]`.
+/// No two adjacent bits are set.
+fn zeckendorf_bits(mut n: u128) -> u128 {
+    let mut bits = 0;
+    for (i, &f) in FIBS.iter().enumerate().rev() {
+        if f <= n {
+            n -= f;
+            bits |= 1 << i;
+        }
+    }
+    bits
+}
+
+/// Decompose `n` into a sum of non-consecutive Fibonacci numbers, largest
+/// first. Zero is the empty sum.
+pub fn zeckendorf(n: u64) -> Vec<u64> {
+    let bits = zeckendorf_bits(u128::from(n));
+    (0..FIBS.len())
+        .rev()
+        .filter(|i| bits >> i & 1 == 1)
+        .map(|i| FIBS[i] as u64)
+        .collect()
+}
+
+/// Encode a sequence of integers with the Fibonacci universal code.
+///
+/// See `FibWriter` for the bit layout.
+pub fn encode(values: &[u64]) -> Vec<u8> {
+    let mut writer = FibWriter::new(Vec::new());
+    for &value in values {
+        writer
+            .write_value(value)
+            .expect("writing to a Vec cannot fail");
+    }
+    writer.finish().expect("writing to a Vec cannot fail")
+}
+
+/// Decode a byte buffer produced by `encode`.
+pub fn decode(bytes: &[u8]) -> io::Result<Vec<u64>> {
+    FibReader::new(bytes).collect()
+}
+
+/// Writes integers as a Fibonacci-coded bitstream.
+///
+/// Each value `n` is stored as the Zeckendorf representation of `n + 1`
+/// (so zero is representable), least significant Fibonacci number first,
+/// followed by an extra `1` bit. Since a Zeckendorf representation never has
+/// two adjacent ones, `11` marks the end of every code word and a reader can
+/// resynchronize after a corrupted byte. Bits are packed most significant
+/// bit first.
+pub struct FibWriter<W: Write> {
+    inner: W,
+    byte: u8,
+    filled: u32,
+}
+
+impl<W: Write> FibWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            byte: 0,
+            filled: 0,
+        }
+    }
+
+    pub fn write_value(&mut self, value: u64) -> io::Result<()> {
+        let bits = zeckendorf_bits(u128::from(value) + 1);
+        let top = u128::BITS - 1 - bits.leading_zeros();
+        for i in 0..=top {
+            self.write_bit(bits >> i & 1 == 1)?;
+        }
+        self.write_bit(true)
+    }
+
+    /// Pad the last byte with zeros and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.filled > 0 {
+            self.byte <<= 8 - self.filled;
+            self.inner.write_all(&[self.byte])?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.byte = self.byte << 1 | u8::from(bit);
+        self.filled += 1;
+        if self.filled == 8 {
+            self.inner.write_all(&[self.byte])?;
+            self.byte = 0;
+            self.filled = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Reads integers from a bitstream written by `FibWriter`.
+pub struct FibReader<R: Read> {
+    inner: R,
+    byte: u8,
+    remaining: u32,
+}
+
+impl<R: Read> FibReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            byte: 0,
+            remaining: 0,
+        }
+    }
+
+    /// Read the next value, or `None` once only padding is left.
+    pub fn read_value(&mut self) -> io::Result<Option<u64>> {
+        let mut value = 0u128;
+        let mut index = 0;
+        let mut previous = false;
+        let mut saw_one = false;
+        loop {
+            let Some(bit) = self.read_bit()? else {
+                if saw_one {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "stream ends in the middle of a code word",
+                    ));
+                }
+                return Ok(None);
+            };
+            if bit && previous {
+                break;
+            }
+            if bit {
+                let Some(&f) = FIBS.get(index) else {
+                    return Err(invalid_data("code word is too long"));
+                };
+                value += f;
+                saw_one = true;
+            }
+            previous = bit;
+            index += 1;
+        }
+        u64::try_from(value - 1)
+            .map(Some)
+            .map_err(|_| invalid_data("value does not fit into a u64"))
+    }
+
+    fn read_bit(&mut self) -> io::Result<Option<bool>> {
+        if self.remaining == 0 {
+            let mut buf = [0];
+            if self.inner.read(&mut buf)? == 0 {
+                return Ok(None);
+            }
+            self.byte = buf[0];
+            self.remaining = 8;
+        }
+        self.remaining -= 1;
+        Ok(Some(self.byte >> self.remaining & 1 == 1))
+    }
+}
+
+impl<R: Read> Iterator for FibReader<R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_value().transpose()
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A small xorshift generator, so the tests do not need a `rand` dependency.
+#[cfg(test)]
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn test_zeckendorf() {
+    assert_eq!(zeckendorf(0), Vec::<u64>::new());
+    assert_eq!(zeckendorf(1), [1]);
+    assert_eq!(zeckendorf(4), [3, 1]);
+    assert_eq!(zeckendorf(64), [55, 8, 1]);
+    assert_eq!(zeckendorf(100), [89, 8, 3]);
+}
+
+#[test]
+fn test_zeckendorf_random() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    for _ in 0..10_000 {
+        let n = xorshift(&mut state) >> (xorshift(&mut state) % 64);
+        let parts = zeckendorf(n);
+        assert_eq!(
+            parts.iter().map(|&p| u128::from(p)).sum::<u128>(),
+            u128::from(n)
+        );
+        let bits = zeckendorf_bits(u128::from(n));
+        assert_eq!(bits & bits >> 1, 0, "adjacent Fibonacci numbers in {n}");
+    }
+    assert_eq!(
+        zeckendorf(u64::MAX)
+            .iter()
+            .map(|&p| u128::from(p))
+            .sum::<u128>(),
+        u128::from(u64::MAX)
+    );
+}
+
+#[test]
+fn test_encode_known_codes() {
+    // 0 -> 1 -> "11", 1 -> 2 -> "011", 2 -> 3 -> "0011", 3 -> 4 -> "1011"
+    assert_eq!(encode(&[0]), [0b1100_0000]);
+    assert_eq!(encode(&[1]), [0b0110_0000]);
+    assert_eq!(encode(&[0, 1, 2, 3]), [0b1101_1001, 0b1101_1000]);
+}
+
+#[test]
+fn test_round_trip_random() {
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    for len in 0..200 {
+        let values: Vec<u64> = (0..len)
+            .map(|_| xorshift(&mut state) >> (xorshift(&mut state) % 64))
+            .collect();
+        assert_eq!(decode(&encode(&values)).unwrap(), values);
+    }
+    let extremes = [0, 1, u64::MAX, u64::MAX - 1, 1 << 63];
+    assert_eq!(decode(&encode(&extremes)).unwrap(), extremes);
+}
+
+#[test]
+fn test_reader_errors() {
+    // A lone "1" bit followed by padding never terminates.
+    let err = decode(&[0b1000_0000]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    // 100 alternating bits are longer than any valid code word.
+    let err = decode(&[0b1010_1010; 13]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_resynchronizes_after_corruption() {
+    let values: Vec<u64> = (0..50).map(|i| i * 37).collect();
+    let mut bytes = encode(&values);
+    bytes[3] = 0;
+    let decoded = decode(&bytes).unwrap();
+    assert!(decoded.ends_with(&values[10..]));
+}
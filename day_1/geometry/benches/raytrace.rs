@@ -0,0 +1,59 @@
+//! Times rendering the demo scene, which mostly comes down to `normalize`,
+//! `magnitude` and dot products.
+//!
+//! Run with `cargo bench -p geometry`. Pass `WIDTHxHEIGHT` to override the
+//! default sizes, e.g. `cargo bench -p geometry -- 1920x1080`, and a path
+//! ending in `.ppm` to also write the last image there.
+
+use geometry::raytrace::{demo_scene, Camera, Image, Scene};
+use std::fs::File;
+use std::hint::black_box;
+use std::io::BufWriter;
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+/// Render `RUNS` times, returning the median time and the last image.
+fn render(scene: &Scene, camera: &Camera, width: usize, height: usize) -> (Duration, Image) {
+    let mut times = Vec::with_capacity(RUNS);
+    let mut image = None;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        image = Some(scene.render(camera, black_box(width), height));
+        times.push(start.elapsed());
+    }
+    times.sort();
+    (times[RUNS / 2], image.unwrap())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut sizes: Vec<(usize, usize)> = args
+        .iter()
+        .filter_map(|arg| {
+            let (width, height) = arg.split_once('x')?;
+            Some((width.parse().ok()?, height.parse().ok()?))
+        })
+        .collect();
+    if sizes.is_empty() {
+        sizes = vec![(320, 240), (640, 480), (1280, 960)];
+    }
+    let output = args.iter().find(|arg| arg.ends_with(".ppm"));
+
+    let (scene, camera) = demo_scene();
+    let mut image = None;
+    for (width, height) in sizes {
+        let (elapsed, last) = render(&scene, &camera, width, height);
+        image = Some(last);
+        let pixels = (width * height) as f64;
+        println!(
+            "{width:>5}x{height:<5}  {elapsed:>10.2?}  {:>6.1} ns/pixel",
+            elapsed.as_secs_f64() * 1e9 / pixels
+        );
+    }
+    if let (Some(path), Some(image)) = (output, image) {
+        image
+            .write_ppm(&mut BufWriter::new(File::create(path).unwrap()))
+            .unwrap();
+    }
+}
@@ -0,0 +1,353 @@
+//! Points and vectors tagged with the coordinate frame they are expressed
+//! in, so that mixing up frames is a compile error:
+//!
+//! ```compile_fail
+//! use geometry::frame::{Camera, Vector, World};
+//!
+//! let a = Vector::<World>::new([1.0, 0.0, 0.0]);
+//! let b = Vector::<Camera>::new([0.0, 1.0, 0.0]);
+//! let sum = a + b;
+//! ```
+//!
+//! Moving between frames takes an explicit [`Transform`]:
+//!
+//! ```
+//! use geometry::frame::{Camera, Point, Transform, Vector, World};
+//!
+//! let eye = Point::<World>::new([0.0, 0.0, 5.0]);
+//! let view = Transform::<World, Camera>::look_at(
+//!     eye,
+//!     Point::ORIGIN,
+//!     Vector::new([0.0, 1.0, 0.0]),
+//! )
+//! .unwrap();
+//! let origin: Point<Camera> = view.transform_point(Point::ORIGIN);
+//! assert_eq!(origin, Point::new([0.0, 0.0, -5.0]));
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::{GeometryError, Matrix4};
+
+/// A marker for coordinate frames. Define more with
+/// `enum Wheel {}` and `impl Frame for Wheel {}`.
+pub trait Frame {}
+
+/// The frame everything else is placed in.
+#[derive(Debug)]
+pub enum World {}
+
+/// The frame of a camera looking down its negative z axis.
+#[derive(Debug)]
+pub enum Camera {}
+
+/// The frame a model is defined in, before it is placed in the world.
+#[derive(Debug)]
+pub enum Object {}
+
+impl Frame for World {}
+impl Frame for Camera {}
+impl Frame for Object {}
+
+/// Holds a frame without owning one, so that the wrappers below are `Copy`,
+/// `Send` and `Sync` whatever the frame type is.
+type Tag<F> = PhantomData<fn() -> F>;
+
+/// A position in the frame `F`.
+pub struct Point<F: Frame> {
+    coords: crate::Vector<3>,
+    frame: Tag<F>,
+}
+
+/// A displacement or direction in the frame `F`.
+pub struct Vector<F: Frame> {
+    coords: crate::Vector<3>,
+    frame: Tag<F>,
+}
+
+/// Maps points and vectors from the frame `From` to the frame `To`.
+///
+/// As with [`Matrix4`], `a * b` applies `b` first, and only compiles if the
+/// frame `b` maps to is the one `a` maps from.
+pub struct Transform<From: Frame, To: Frame> {
+    matrix: Matrix4,
+    frames: PhantomData<fn(From) -> To>,
+}
+
+impl<F: Frame> Point<F> {
+    pub const ORIGIN: Self = Self::new([0.0; 3]);
+
+    pub const fn new(coords: [f64; 3]) -> Self {
+        Self {
+            coords: crate::Vector::new(coords),
+            frame: PhantomData,
+        }
+    }
+
+    pub fn coords(&self) -> &[f64; 3] {
+        self.coords.coords()
+    }
+
+    pub fn distance(self, other: Self) -> f64 {
+        self.coords.distance(other.coords)
+    }
+
+    /// Linear interpolation: `self` at `t = 0` and `other` at `t = 1`.
+    pub fn lerp(self, other: Self, t: f64) -> Self {
+        Self::from(self.coords.lerp(other.coords, t))
+    }
+}
+
+impl<F: Frame> Vector<F> {
+    pub const ZERO: Self = Self::new([0.0; 3]);
+
+    pub const fn new(coords: [f64; 3]) -> Self {
+        Self {
+            coords: crate::Vector::new(coords),
+            frame: PhantomData,
+        }
+    }
+
+    pub fn coords(&self) -> &[f64; 3] {
+        self.coords.coords()
+    }
+
+    pub fn dot(self, other: Self) -> f64 {
+        self.coords.dot(other.coords)
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self::from(self.coords.cross(other.coords))
+    }
+
+    /// See [`crate::Vector::magnitude`].
+    pub fn magnitude(self) -> f64 {
+        self.coords.magnitude()
+    }
+
+    /// See [`crate::Vector::normalize`].
+    pub fn normalize(self) -> Self {
+        Self::from(self.coords.normalize())
+    }
+
+    /// See [`crate::Vector::try_normalize`].
+    pub fn try_normalize(self) -> Result<Self, GeometryError> {
+        Ok(Self::from(self.coords.try_normalize()?))
+    }
+}
+
+impl<From: Frame, To: Frame> Transform<From, To> {
+    /// Declare that `matrix` maps coordinates in `From` to coordinates in
+    /// `To`. This is the one place where the frames are not checked.
+    pub const fn new(matrix: Matrix4) -> Self {
+        Self {
+            matrix,
+            frames: PhantomData,
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix4 {
+        &self.matrix
+    }
+
+    /// The view transform of a camera at `eye` looking at `target`; see
+    /// [`Matrix4::look_at`].
+    pub fn look_at(
+        eye: Point<From>,
+        target: Point<From>,
+        up: Vector<From>,
+    ) -> Result<Self, GeometryError> {
+        Ok(Self::new(Matrix4::look_at(
+            eye.coords,
+            target.coords,
+            up.coords,
+        )?))
+    }
+
+    pub fn transform_point(&self, point: Point<From>) -> Point<To> {
+        Point::from(self.matrix.transform_point(point.coords))
+    }
+
+    pub fn transform_vector(&self, vector: Vector<From>) -> Vector<To> {
+        Vector::from(self.matrix.transform_vector(vector.coords))
+    }
+}
+
+impl<F: Frame> Transform<F, F> {
+    pub const IDENTITY: Self = Self::new(Matrix4::IDENTITY);
+}
+
+impl<F: Frame> From<crate::Vector<3>> for Point<F> {
+    fn from(coords: crate::Vector<3>) -> Self {
+        Self {
+            coords,
+            frame: PhantomData,
+        }
+    }
+}
+
+impl<F: Frame> From<crate::Vector<3>> for Vector<F> {
+    fn from(coords: crate::Vector<3>) -> Self {
+        Self {
+            coords,
+            frame: PhantomData,
+        }
+    }
+}
+
+impl<A: Frame, B: Frame, C: Frame> Mul<Transform<A, B>> for Transform<B, C> {
+    type Output = Transform<A, C>;
+
+    fn mul(self, other: Transform<A, B>) -> Transform<A, C> {
+        Transform::new(self.matrix * other.matrix)
+    }
+}
+
+impl<F: Frame> Add<Vector<F>> for Point<F> {
+    type Output = Self;
+
+    fn add(self, other: Vector<F>) -> Self {
+        Self::from(self.coords + other.coords)
+    }
+}
+
+impl<F: Frame> Sub<Vector<F>> for Point<F> {
+    type Output = Self;
+
+    fn sub(self, other: Vector<F>) -> Self {
+        Self::from(self.coords - other.coords)
+    }
+}
+
+impl<F: Frame> Sub for Point<F> {
+    type Output = Vector<F>;
+
+    /// The vector from `other` to `self`.
+    fn sub(self, other: Self) -> Vector<F> {
+        Vector::from(self.coords - other.coords)
+    }
+}
+
+impl<F: Frame> Add for Vector<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from(self.coords + other.coords)
+    }
+}
+
+impl<F: Frame> Sub for Vector<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::from(self.coords - other.coords)
+    }
+}
+
+impl<F: Frame> Mul<f64> for Vector<F> {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self {
+        Self::from(self.coords * factor)
+    }
+}
+
+impl<F: Frame> Neg for Vector<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from(-self.coords)
+    }
+}
+
+// Derives would require the frames themselves to implement these traits.
+macro_rules! impl_common {
+    ($name:ident<$($frame:ident),+>, $accessor:ident) => {
+        impl<$($frame: Frame),+> Clone for $name<$($frame),+> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<$($frame: Frame),+> Copy for $name<$($frame),+> {}
+
+        impl<$($frame: Frame),+> PartialEq for $name<$($frame),+> {
+            fn eq(&self, other: &Self) -> bool {
+                self.$accessor() == other.$accessor()
+            }
+        }
+
+        impl<$($frame: Frame),+> fmt::Debug for $name<$($frame),+> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                // `Point<World>` rather than `Point<geometry::frame::World>`.
+                let frames: Vec<_> = [$(std::any::type_name::<$frame>()),+]
+                    .into_iter()
+                    .map(|frame| frame.rsplit("::").next().unwrap_or(frame))
+                    .collect();
+                let name = format!("{}<{}>", stringify!($name), frames.join(", "));
+                f.debug_tuple(&name).field(self.$accessor()).finish()
+            }
+        }
+    };
+}
+
+impl_common!(Point<F>, coords);
+impl_common!(Vector<F>, coords);
+impl_common!(Transform<A, B>, matrix);
+
+#[test]
+fn test_points_and_vectors() {
+    let p = Point::<World>::new([1.0, 2.0, 3.0]);
+    let q = Point::<World>::new([4.0, 6.0, 3.0]);
+    let d = q - p;
+    assert_eq!(d, Vector::new([3.0, 4.0, 0.0]));
+    assert_eq!(d.magnitude(), 5.0);
+    assert_eq!(p.distance(q), 5.0);
+    assert_eq!(p + d, q);
+    assert_eq!(q - d, p);
+    assert_eq!(p.lerp(q, 0.5), Point::new([2.5, 4.0, 3.0]));
+    assert_eq!(-d * 2.0 + d, Vector::new([-3.0, -4.0, 0.0]));
+    assert_eq!(d.normalize(), Vector::new([0.6, 0.8, 0.0]));
+    assert_eq!(d.dot(d), 25.0);
+    let x = Vector::<Object>::new([1.0, 0.0, 0.0]);
+    let y = Vector::<Object>::new([0.0, 1.0, 0.0]);
+    assert_eq!(x.cross(y), Vector::new([0.0, 0.0, 1.0]));
+    assert_eq!(
+        Vector::<Camera>::ZERO.try_normalize(),
+        Err(GeometryError::ZeroVector)
+    );
+    assert_eq!(format!("{p:?}"), "Point<World>([1.0, 2.0, 3.0])");
+}
+
+#[test]
+fn test_transform() {
+    // The object sits 10 units along x in the world, and the camera looks at
+    // it from 5 units above.
+    let place =
+        Transform::<Object, World>::new(Matrix4::translation(crate::Vector::new([10.0, 0.0, 0.0])));
+    let view = Transform::<World, Camera>::look_at(
+        Point::new([10.0, 5.0, 0.0]),
+        Point::new([10.0, 0.0, 0.0]),
+        Vector::new([0.0, 0.0, -1.0]),
+    )
+    .unwrap();
+    let object_to_camera: Transform<Object, Camera> = view * place;
+    assert_eq!(
+        object_to_camera.transform_point(Point::ORIGIN),
+        Point::new([0.0, 0.0, -5.0])
+    );
+    // Directions are not translated.
+    let up = Vector::<Object>::new([0.0, 1.0, 0.0]);
+    assert_eq!(place.transform_vector(up), Vector::new([0.0, 1.0, 0.0]));
+    assert_eq!(
+        object_to_camera.transform_vector(up),
+        Vector::new([0.0, 0.0, 1.0])
+    );
+    assert_eq!(view * Transform::IDENTITY, view);
+    assert_eq!(
+        format!("{place:?}"),
+        format!("Transform<Object, World>({:?})", place.matrix())
+    );
+}
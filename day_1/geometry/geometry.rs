@@ -1,24 +1,4 @@
-// Calculate the magnitude of a vector by summing the squares of its coordinates
-// and taking the square root. Use the `sqrt()` method to calculate the square
-// root, like `v.sqrt()`.
-fn magnitude(coords: &[f64; 3]) -> f64 {
-    let mut magnitude = 0.0;
-    for coord in coords {
-        magnitude += coord.powi(2);
-    }
-    magnitude.sqrt()
-}
-
-// Normalize a vector by calculating its magnitude and dividing all of its
-// coordinates by that magnitude.
-fn normalize(coords: &mut [f64; 3]) {
-    let magnitude = magnitude(coords);
-    for coord in coords {
-        *coord /= magnitude;
-    }
-}
-
-// Use the following `main` to test your work.
+use geometry::{magnitude, normalize, Vector};
 
 fn main() {
     println!(
@@ -30,4 +10,9 @@ fn main() {
     println!("Magnitude of {v:?}: {}", magnitude(&v));
     normalize(&mut v);
     println!("Magnitude of {v:?} after normalization: {}", magnitude(&v));
+
+    let a = Vector::new([1.0, 0.0, 0.0]);
+    let b = Vector::new([0.0, 1.0, 0.0]);
+    println!("{a:?} x {b:?} = {:?}", a.cross(b));
+    println!("Angle between them: {}", a.angle(b).to_degrees());
 }
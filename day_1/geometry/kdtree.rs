@@ -0,0 +1,286 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::Vector;
+
+/// A k-d tree over a fixed set of points in `N`-dimensional space, for
+/// finding nearest neighbours without comparing every pair of points.
+///
+/// Queries return the points' indices in the slice the tree was built from,
+/// along with their distances to the query point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KdTree<const N: usize> {
+    /// The points with their input indices, arranged so that the middle
+    /// element of every subrange splits the rest of it along the axis for its
+    /// depth, and is the root of the subtree for that range.
+    nodes: Vec<(usize, Vector<N>)>,
+}
+
+impl<const N: usize> KdTree<N> {
+    /// Build a balanced tree by recursively splitting at the median, in
+    /// O(n log n) time.
+    pub fn new(points: &[Vector<N>]) -> Self {
+        const { assert!(N > 0, "a k-d tree needs at least one dimension") };
+        let mut nodes: Vec<_> = points.iter().copied().enumerate().collect();
+        build(&mut nodes, 0);
+        Self { nodes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The closest point to `query`, or `None` if the tree is empty.
+    pub fn nearest(&self, query: Vector<N>) -> Option<(usize, f64)> {
+        self.k_nearest(query, 1).pop()
+    }
+
+    /// The `k` closest points to `query`, nearest first. Equally distant
+    /// points are ordered by index.
+    pub fn k_nearest(&self, query: Vector<N>, k: usize) -> Vec<(usize, f64)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut nearest = Nearest {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        };
+        search(&self.nodes, 0, query, &mut nearest);
+        nearest
+            .heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|neighbour| (neighbour.index, neighbour.distance))
+            .collect()
+    }
+
+    /// All points at most `radius` away from `query`, nearest first. Equally
+    /// distant points are ordered by index.
+    pub fn within_radius(&self, query: Vector<N>, radius: f64) -> Vec<(usize, f64)> {
+        let mut within = WithinRadius {
+            radius,
+            found: Vec::new(),
+        };
+        search(&self.nodes, 0, query, &mut within);
+        within
+            .found
+            .sort_unstable_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        within.found
+    }
+}
+
+fn build<const N: usize>(nodes: &mut [(usize, Vector<N>)], axis: usize) {
+    if nodes.len() <= 1 {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    nodes.select_nth_unstable_by(mid, |(_, a), (_, b)| a[axis].total_cmp(&b[axis]));
+    let (left, right) = nodes.split_at_mut(mid);
+    build(left, (axis + 1) % N);
+    build(&mut right[1..], (axis + 1) % N);
+}
+
+/// Collects the results of a search.
+trait Neighbours {
+    fn insert(&mut self, index: usize, distance: f64);
+
+    /// How far away points may be to be inserted; subtrees further away are
+    /// skipped.
+    fn bound(&self) -> f64;
+}
+
+fn search<const N: usize>(
+    nodes: &[(usize, Vector<N>)],
+    axis: usize,
+    query: Vector<N>,
+    neighbours: &mut impl Neighbours,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    let (index, point) = nodes[mid];
+    neighbours.insert(index, point.distance(query));
+    // Points equal to the median along the axis can be on either side, but
+    // then the offset is 0 and both sides are searched.
+    let offset = query[axis] - point[axis];
+    let (near, far) = if offset < 0.0 {
+        (&nodes[..mid], &nodes[mid + 1..])
+    } else {
+        (&nodes[mid + 1..], &nodes[..mid])
+    };
+    search(near, (axis + 1) % N, query, neighbours);
+    if offset.abs() <= neighbours.bound() {
+        search(far, (axis + 1) % N, query, neighbours);
+    }
+}
+
+/// A candidate neighbour, ordered by distance and then by index.
+#[derive(Debug, Clone, Copy)]
+struct Neighbour {
+    distance: f64,
+    index: usize,
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Neighbour {}
+
+/// The `k` nearest points so far, with the furthest of them on top.
+struct Nearest {
+    k: usize,
+    heap: BinaryHeap<Neighbour>,
+}
+
+impl Neighbours for Nearest {
+    fn insert(&mut self, index: usize, distance: f64) {
+        let neighbour = Neighbour { distance, index };
+        if self.heap.len() < self.k {
+            self.heap.push(neighbour);
+        } else if self
+            .heap
+            .peek()
+            .is_some_and(|furthest| neighbour < *furthest)
+        {
+            self.heap.pop();
+            self.heap.push(neighbour);
+        }
+    }
+
+    fn bound(&self) -> f64 {
+        match self.heap.peek() {
+            Some(furthest) if self.heap.len() == self.k => furthest.distance,
+            _ => f64::INFINITY,
+        }
+    }
+}
+
+struct WithinRadius {
+    radius: f64,
+    found: Vec<(usize, f64)>,
+}
+
+impl Neighbours for WithinRadius {
+    fn insert(&mut self, index: usize, distance: f64) {
+        if distance <= self.radius {
+            self.found.push((index, distance));
+        }
+    }
+
+    fn bound(&self) -> f64 {
+        self.radius
+    }
+}
+
+#[cfg(test)]
+fn random_points<const N: usize>(count: usize, seed: u64) -> Vec<Vector<N>> {
+    let mut state = seed;
+    // Few distinct values, so that there are ties and duplicates.
+    let mut next = move || (crate::xorshift(&mut state) % 16) as f64 / 4.0;
+    (0..count)
+        .map(|_| Vector::new(std::array::from_fn(|_| next())))
+        .collect()
+}
+
+#[cfg(test)]
+fn brute_force<const N: usize>(points: &[Vector<N>], query: Vector<N>) -> Vec<(usize, f64)> {
+    let mut all: Vec<_> = points
+        .iter()
+        .map(|p| p.distance(query))
+        .enumerate()
+        .collect();
+    all.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    all
+}
+
+#[test]
+fn test_nearest() {
+    let points = [[0.0, 0.0], [3.0, 4.0], [-1.0, 1.0], [10.0, 0.0]].map(Vector::new);
+    let tree = KdTree::new(&points);
+    assert_eq!(tree.len(), 4);
+    assert_eq!(
+        tree.nearest(Vector::new([2.0, 3.0])),
+        Some((1, 2.0_f64.sqrt()))
+    );
+    assert_eq!(tree.nearest(Vector::new([9.0, 0.0])), Some((3, 1.0)));
+    assert_eq!(
+        tree.k_nearest(Vector::new([0.0, 0.0]), 2),
+        vec![(0, 0.0), (2, 2.0_f64.sqrt())]
+    );
+    assert_eq!(tree.k_nearest(Vector::ZERO, 0), vec![]);
+    assert_eq!(tree.k_nearest(Vector::ZERO, 10).len(), 4);
+    assert_eq!(
+        tree.within_radius(Vector::new([0.0, 0.0]), 5.0),
+        vec![(0, 0.0), (2, 2.0_f64.sqrt()), (1, 5.0)]
+    );
+
+    let empty = KdTree::<3>::new(&[]);
+    assert!(empty.is_empty());
+    assert_eq!(empty.nearest(Vector::ZERO), None);
+    assert_eq!(empty.within_radius(Vector::ZERO, 1.0), vec![]);
+}
+
+#[test]
+fn test_matches_brute_force() {
+    let points = random_points::<3>(500, 0x9e3779b97f4a7c15);
+    let tree = KdTree::new(&points);
+    for query in random_points::<3>(50, 42) {
+        let expected = brute_force(&points, query);
+        for k in [1, 5, 40] {
+            assert_eq!(tree.k_nearest(query, k), expected[..k]);
+        }
+        for radius in [0.0, 0.5, 1.5] {
+            let within: Vec<_> = expected
+                .iter()
+                .copied()
+                .take_while(|&(_, d)| d <= radius)
+                .collect();
+            assert_eq!(tree.within_radius(query, radius), within);
+        }
+    }
+}
+
+#[test]
+fn test_deduplicate() {
+    // Keep the first of every group of points closer than a tolerance.
+    let points = random_points::<2>(4000, 7);
+    let tree = KdTree::new(&points);
+    let mut duplicate = vec![false; points.len()];
+    let mut kept = Vec::new();
+    for (i, &point) in points.iter().enumerate() {
+        if duplicate[i] {
+            continue;
+        }
+        kept.push(i);
+        for (j, _) in tree.within_radius(point, 1e-9) {
+            duplicate[j] = true;
+        }
+    }
+    // The coordinates are quarters from 0 to 3.75.
+    assert_eq!(kept.len(), 16 * 16);
+    for (n, &i) in kept.iter().enumerate() {
+        assert!(kept[..n].iter().all(|&j| points[i] != points[j]));
+    }
+}
@@ -0,0 +1,82 @@
+use thiserror::Error;
+
+pub mod frame;
+mod kdtree;
+mod polygon;
+mod quaternion;
+pub mod raytrace;
+mod transform;
+mod vector;
+
+pub use kdtree::KdTree;
+pub use polygon::{orientation, Orientation, Polygon, Segment, SegmentIntersection};
+pub use quaternion::Quaternion;
+pub use transform::Matrix4;
+pub use vector::Vector;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GeometryError {
+    #[error("the zero vector has no direction")]
+    ZeroVector,
+    #[error("the vector has infinite or NaN coordinates")]
+    NonFinite,
+    #[error("invalid projection: {0}")]
+    InvalidProjection(&'static str),
+    #[error("the polygon has no area")]
+    Degenerate,
+}
+
+/// The magnitude of a vector given as an array. See
+/// [`Vector::magnitude`].
+pub fn magnitude<const N: usize>(coords: &[f64; N]) -> f64 {
+    Vector::new(*coords).magnitude()
+}
+
+/// Normalize a vector given as an array in place. See
+/// [`Vector::normalize`].
+pub fn normalize<const N: usize>(coords: &mut [f64; N]) {
+    *coords = Vector::new(*coords).normalize().into();
+}
+
+/// Normalize a vector, leaving it unchanged if it is the zero vector or has
+/// non-finite coordinates.
+pub fn try_normalize<const N: usize>(coords: &mut [f64; N]) -> Result<(), GeometryError> {
+    *coords = Vector::new(*coords).try_normalize()?.into();
+    Ok(())
+}
+
+/// Step a xorshift64 generator, for reproducible test data.
+#[cfg(test)]
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn test_magnitude() {
+    assert_eq!(magnitude(&[0.0, 1.0, 0.0]), 1.0);
+    assert_eq!(magnitude(&[3.0, 4.0]), 5.0);
+    assert_eq!(magnitude(&[1.0, 1.0, 1.0, 1.0]), 2.0);
+}
+
+#[test]
+fn test_normalize() {
+    let mut v = [1.0, 2.0, 9.0];
+    normalize(&mut v);
+    assert!((magnitude(&v) - 1.0).abs() < 1e-15);
+    let mut v = [0.0, -2.0];
+    normalize(&mut v);
+    assert_eq!(v, [0.0, -1.0]);
+}
+
+#[test]
+fn test_try_normalize() {
+    let mut v = [0.0; 3];
+    assert_eq!(try_normalize(&mut v), Err(GeometryError::ZeroVector));
+    assert_eq!(v, [0.0; 3]);
+    let mut v = [1e200, 0.0, 1e200];
+    assert_eq!(try_normalize(&mut v), Ok(()));
+    assert!((magnitude(&v) - 1.0).abs() < 1e-15);
+}
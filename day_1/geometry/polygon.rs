@@ -0,0 +1,516 @@
+use crate::{GeometryError, Vector};
+
+/// The turn made by walking from `a` over `b` to `c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    Collinear,
+}
+
+/// Relative error bound of the floating-point orientation determinant,
+/// `(3 + 16 eps) eps`, from Shewchuk's "Adaptive Precision Floating-Point
+/// Arithmetic and Fast Robust Geometric Predicates".
+const ORIENTATION_ERROR_BOUND: f64 = (3.0 + 16.0 * f64::EPSILON) * f64::EPSILON;
+
+/// `a + b` as the rounded sum and its rounding error.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    (sum, (a - a_virtual) + (b - b_virtual))
+}
+
+/// `a * b` as the rounded product and its rounding error.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    (product, a.mul_add(b, -product))
+}
+
+/// The sign of the exact sum of `terms`. The terms are accumulated into a
+/// floating-point expansion, a list of non-overlapping components ordered by
+/// magnitude whose sum is exact, so the largest non-zero component has the
+/// sign of the sum.
+fn exact_sign(terms: &[f64]) -> f64 {
+    let mut expansion: Vec<f64> = Vec::with_capacity(terms.len());
+    for &term in terms {
+        let mut carry = term;
+        for component in &mut expansion {
+            let (sum, error) = two_sum(carry, *component);
+            *component = error;
+            carry = sum;
+        }
+        expansion.push(carry);
+    }
+    expansion
+        .iter()
+        .rev()
+        .find(|&&x| x != 0.0)
+        .map_or(0.0, |x| x.signum())
+}
+
+/// Whether `c` is left of, right of or on the line through `a` and `b`.
+///
+/// The answer is exact for all finite inputs. The determinant is first
+/// computed in plain floating point, and only recomputed exactly if it is too
+/// close to zero for its sign to be trusted.
+pub fn orientation(a: Vector<2>, b: Vector<2>, c: Vector<2>) -> Orientation {
+    let left = (a[0] - c[0]) * (b[1] - c[1]);
+    let right = (a[1] - c[1]) * (b[0] - c[0]);
+    let det = left - right;
+    let bound = ORIENTATION_ERROR_BOUND * (left.abs() + right.abs());
+    let sign = if det.abs() > bound {
+        det.signum()
+    } else {
+        // Expanded, the determinant is a sum of six products of inputs.
+        let mut terms = Vec::with_capacity(12);
+        for (x, y, sign) in [
+            (a[0], b[1], 1.0),
+            (a[0], c[1], -1.0),
+            (c[0], b[1], -1.0),
+            (a[1], b[0], -1.0),
+            (a[1], c[0], 1.0),
+            (c[1], b[0], 1.0),
+        ] {
+            let (product, error) = two_product(sign * x, y);
+            terms.extend([product, error]);
+        }
+        exact_sign(&terms)
+    };
+    if sign > 0.0 {
+        Orientation::CounterClockwise
+    } else if sign < 0.0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+/// A line segment between two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: Vector<2>,
+    pub end: Vector<2>,
+}
+
+/// How two segments intersect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentIntersection {
+    None,
+    Point(Vector<2>),
+    /// The segments are collinear and share this part.
+    Overlap(Segment),
+}
+
+impl Segment {
+    pub fn new(start: Vector<2>, end: Vector<2>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn length(&self) -> f64 {
+        self.start.distance(self.end)
+    }
+
+    /// Whether `point` lies on the segment, endpoints included.
+    pub fn contains(&self, point: Vector<2>) -> bool {
+        orientation(self.start, self.end, point) == Orientation::Collinear
+            && self.in_bounding_box(point)
+    }
+
+    /// For a point on the line through the segment, whether it lies on the
+    /// segment.
+    fn in_bounding_box(&self, point: Vector<2>) -> bool {
+        (0..2).all(|i| {
+            let (low, high) = if self.start[i] <= self.end[i] {
+                (self.start[i], self.end[i])
+            } else {
+                (self.end[i], self.start[i])
+            };
+            (low..=high).contains(&point[i])
+        })
+    }
+
+    /// Whether the segments share at least one point. Exact, as it only
+    /// depends on orientations and comparisons.
+    pub fn intersects(&self, other: &Segment) -> bool {
+        let d1 = orientation(other.start, other.end, self.start);
+        let d2 = orientation(other.start, other.end, self.end);
+        let d3 = orientation(self.start, self.end, other.start);
+        let d4 = orientation(self.start, self.end, other.end);
+        let straddles = |a, b| {
+            (a, b) == (Orientation::Clockwise, Orientation::CounterClockwise)
+                || (a, b) == (Orientation::CounterClockwise, Orientation::Clockwise)
+        };
+        if straddles(d1, d2) && straddles(d3, d4) {
+            return true;
+        }
+        (d1 == Orientation::Collinear && other.in_bounding_box(self.start))
+            || (d2 == Orientation::Collinear && other.in_bounding_box(self.end))
+            || (d3 == Orientation::Collinear && self.in_bounding_box(other.start))
+            || (d4 == Orientation::Collinear && self.in_bounding_box(other.end))
+    }
+
+    /// Where the segments intersect. Whether they do is decided exactly by
+    /// `intersects`; the location of a crossing is rounded.
+    pub fn intersection(&self, other: &Segment) -> SegmentIntersection {
+        if !self.intersects(other) {
+            return SegmentIntersection::None;
+        }
+        let collinear = orientation(self.start, self.end, other.start) == Orientation::Collinear
+            && orientation(self.start, self.end, other.end) == Orientation::Collinear;
+        if !collinear {
+            // Solve start + t * r = other.start + u * s for t.
+            let r = self.end - self.start;
+            let s = other.end - other.start;
+            let cross = |a: Vector<2>, b: Vector<2>| a[0] * b[1] - a[1] * b[0];
+            let t = cross(other.start - self.start, s) / cross(r, s);
+            return SegmentIntersection::Point(self.start + r * t.clamp(0.0, 1.0));
+        }
+        // Order the four points along the line; the middle two bound the
+        // overlap.
+        let axis = if (self.end[0] - self.start[0]).abs() >= (self.end[1] - self.start[1]).abs() {
+            0
+        } else {
+            1
+        };
+        let mut points = [self.start, self.end, other.start, other.end];
+        let key = |p: &Vector<2>| p[axis];
+        points[..2].sort_by(|a, b| key(a).total_cmp(&key(b)));
+        points[2..].sort_by(|a, b| key(a).total_cmp(&key(b)));
+        let low = if key(&points[0]) >= key(&points[2]) {
+            points[0]
+        } else {
+            points[2]
+        };
+        let high = if key(&points[1]) <= key(&points[3]) {
+            points[1]
+        } else {
+            points[3]
+        };
+        if low == high {
+            SegmentIntersection::Point(low)
+        } else {
+            SegmentIntersection::Overlap(Segment::new(low, high))
+        }
+    }
+}
+
+/// A closed polygon in the plane, given by its vertices in order. The last
+/// vertex connects back to the first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Vector<2>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Vector<2>>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Vector<2>] {
+        &self.vertices
+    }
+
+    /// The edges, from each vertex to the next.
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| Segment::new(self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// The area by the shoelace formula, positive if the vertices run
+    /// counterclockwise and negative if clockwise.
+    pub fn signed_area(&self) -> f64 {
+        let Some(&origin) = self.vertices.first() else {
+            return 0.0;
+        };
+        // Measuring from the first vertex keeps the products small.
+        let twice_area: f64 = self
+            .edges()
+            .map(|edge| {
+                let (a, b) = (edge.start - origin, edge.end - origin);
+                a[0] * b[1] - a[1] * b[0]
+            })
+            .sum();
+        twice_area / 2.0
+    }
+
+    pub fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges().map(|edge| edge.length()).sum()
+    }
+
+    /// The center of mass of the enclosed area. Fails if the area is 0.
+    pub fn centroid(&self) -> Result<Vector<2>, GeometryError> {
+        let area = self.signed_area();
+        if area == 0.0 || !area.is_finite() {
+            return Err(GeometryError::Degenerate);
+        }
+        let origin = self.vertices[0];
+        let mut sum = Vector::ZERO;
+        for edge in self.edges() {
+            let (a, b) = (edge.start - origin, edge.end - origin);
+            sum = sum + (a + b) * (a[0] * b[1] - a[1] * b[0]);
+        }
+        Ok(origin + sum * (1.0 / (6.0 * area)))
+    }
+
+    /// How many times the boundary winds counterclockwise around `point`,
+    /// negative for clockwise. Points on the boundary have no well-defined
+    /// winding number; use `contains` for those.
+    pub fn winding_number(&self, point: Vector<2>) -> i32 {
+        let mut winding = 0;
+        for edge in self.edges() {
+            if edge.start[1] <= point[1] {
+                // An upward crossing with the point on its left.
+                if edge.end[1] > point[1]
+                    && orientation(edge.start, edge.end, point) == Orientation::CounterClockwise
+                {
+                    winding += 1;
+                }
+            } else if edge.end[1] <= point[1]
+                && orientation(edge.start, edge.end, point) == Orientation::Clockwise
+            {
+                // A downward crossing with the point on its right.
+                winding -= 1;
+            }
+        }
+        winding
+    }
+
+    /// Whether `point` is inside the polygon or on its boundary, by the
+    /// non-zero winding rule.
+    pub fn contains(&self, point: Vector<2>) -> bool {
+        self.winding_number(point) != 0 || self.edges().any(|edge| edge.contains(point))
+    }
+
+    /// The smallest convex polygon containing all `points`, counterclockwise
+    /// from the leftmost point (the lowest one if there are several). Points
+    /// on its edges are not vertices.
+    ///
+    /// This is Andrew's monotone chain algorithm: sort the points, then build
+    /// the lower hull from left to right and the upper hull back, dropping
+    /// every point where the hull would not turn left.
+    pub fn convex_hull(points: &[Vector<2>]) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+        sorted.dedup();
+        if sorted.len() < 3 {
+            return Self::new(sorted);
+        }
+        let turns_left = |hull: &[Vector<2>], point| {
+            orientation(hull[hull.len() - 2], hull[hull.len() - 1], point)
+                == Orientation::CounterClockwise
+        };
+        let mut hull: Vec<Vector<2>> = Vec::with_capacity(sorted.len() + 1);
+        for &point in &sorted {
+            while hull.len() >= 2 && !turns_left(&hull, point) {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The upper hull must not eat into the lower one.
+        let lower = hull.len() + 1;
+        for &point in sorted.iter().rev().skip(1) {
+            while hull.len() >= lower && !turns_left(&hull, point) {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The upper hull ends where the lower one started.
+        hull.pop();
+        Self::new(hull)
+    }
+}
+
+#[cfg(test)]
+fn v(x: f64, y: f64) -> Vector<2> {
+    Vector::new([x, y])
+}
+
+#[test]
+fn test_orientation() {
+    let (a, b) = (v(0.0, 0.0), v(1.0, 0.0));
+    assert_eq!(
+        orientation(a, b, v(0.5, 1.0)),
+        Orientation::CounterClockwise
+    );
+    assert_eq!(orientation(a, b, v(0.5, -1.0)), Orientation::Clockwise);
+    assert_eq!(orientation(a, b, v(7.0, 0.0)), Orientation::Collinear);
+}
+
+#[test]
+fn test_orientation_is_exact() {
+    // The point is one unit in the last place above the line y = x, which
+    // the plain floating-point determinant rounds to exactly 0.
+    let (p, q, r) = (v(0.5, 0.5000000000000001), v(12.0, 12.0), v(24.0, 24.0));
+    let naive = (p[0] - r[0]) * (q[1] - r[1]) - (p[1] - r[1]) * (q[0] - r[0]);
+    assert_eq!(naive, 0.0);
+    assert_eq!(orientation(p, q, r), Orientation::CounterClockwise);
+    assert_eq!(orientation(q, p, r), Orientation::Clockwise);
+    assert_eq!(orientation(v(0.5, 0.5), q, r), Orientation::Collinear);
+}
+
+#[test]
+fn test_segment_intersection() {
+    let s = Segment::new(v(0.0, 0.0), v(4.0, 4.0));
+    let t = Segment::new(v(0.0, 4.0), v(4.0, 0.0));
+    assert!(s.intersects(&t));
+    assert_eq!(s.intersection(&t), SegmentIntersection::Point(v(2.0, 2.0)));
+
+    // Touching at an endpoint.
+    let u = Segment::new(v(4.0, 4.0), v(5.0, 0.0));
+    assert_eq!(s.intersection(&u), SegmentIntersection::Point(v(4.0, 4.0)));
+    // Parallel and disjoint.
+    let w = Segment::new(v(0.0, 1.0), v(4.0, 5.0));
+    assert!(!s.intersects(&w));
+    assert_eq!(s.intersection(&w), SegmentIntersection::None);
+    // Collinear but disjoint.
+    let far = Segment::new(v(5.0, 5.0), v(6.0, 6.0));
+    assert_eq!(s.intersection(&far), SegmentIntersection::None);
+    // Collinear and overlapping, in either direction.
+    let overlap = Segment::new(v(6.0, 6.0), v(2.0, 2.0));
+    assert_eq!(
+        s.intersection(&overlap),
+        SegmentIntersection::Overlap(Segment::new(v(2.0, 2.0), v(4.0, 4.0)))
+    );
+    // Collinear, touching end to end.
+    let next = Segment::new(v(4.0, 4.0), v(8.0, 8.0));
+    assert_eq!(
+        s.intersection(&next),
+        SegmentIntersection::Point(v(4.0, 4.0))
+    );
+    // A T junction, with the foot on the other segment's interior.
+    let foot = Segment::new(v(1.0, 3.0), v(2.0, 2.0));
+    assert_eq!(
+        s.intersection(&foot),
+        SegmentIntersection::Point(v(2.0, 2.0))
+    );
+    // Vertical segments.
+    let vertical = Segment::new(v(1.0, -1.0), v(1.0, 3.0));
+    let other = Segment::new(v(1.0, 2.0), v(1.0, 9.0));
+    assert_eq!(
+        vertical.intersection(&other),
+        SegmentIntersection::Overlap(Segment::new(v(1.0, 2.0), v(1.0, 3.0)))
+    );
+}
+
+#[test]
+fn test_area_perimeter_centroid() {
+    let square = Polygon::new(vec![v(1.0, 1.0), v(3.0, 1.0), v(3.0, 3.0), v(1.0, 3.0)]);
+    assert_eq!(square.signed_area(), 4.0);
+    assert_eq!(square.perimeter(), 8.0);
+    assert_eq!(square.centroid(), Ok(v(2.0, 2.0)));
+
+    let clockwise = Polygon::new(square.vertices().iter().rev().copied().collect());
+    assert_eq!(clockwise.signed_area(), -4.0);
+    assert_eq!(clockwise.area(), 4.0);
+    assert_eq!(clockwise.centroid(), Ok(v(2.0, 2.0)));
+
+    // An L made of a 2x1 and a 1x1 box.
+    let l = Polygon::new(vec![
+        v(0.0, 0.0),
+        v(2.0, 0.0),
+        v(2.0, 1.0),
+        v(1.0, 1.0),
+        v(1.0, 2.0),
+        v(0.0, 2.0),
+    ]);
+    assert_eq!(l.area(), 3.0);
+    assert_eq!(l.perimeter(), 8.0);
+    let centroid = l.centroid().unwrap();
+    assert!(centroid.distance(v(5.0 / 6.0, 5.0 / 6.0)) < 1e-15);
+
+    // Far from the origin, the shoelace formula must not cancel.
+    let shifted = Polygon::new(square.vertices().iter().map(|&p| p + v(1e9, 1e9)).collect());
+    assert_eq!(shifted.area(), 4.0);
+    assert_eq!(shifted.centroid(), Ok(v(1e9 + 2.0, 1e9 + 2.0)));
+
+    let line = Polygon::new(vec![v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0)]);
+    assert_eq!(line.centroid(), Err(GeometryError::Degenerate));
+    assert_eq!(Polygon::new(vec![]).area(), 0.0);
+}
+
+#[test]
+fn test_point_in_polygon() {
+    let l = Polygon::new(vec![
+        v(0.0, 0.0),
+        v(2.0, 0.0),
+        v(2.0, 1.0),
+        v(1.0, 1.0),
+        v(1.0, 2.0),
+        v(0.0, 2.0),
+    ]);
+    assert_eq!(l.winding_number(v(0.5, 0.5)), 1);
+    assert_eq!(l.winding_number(v(1.5, 1.5)), 0);
+    assert!(l.contains(v(0.5, 1.5)));
+    assert!(!l.contains(v(1.5, 1.5)));
+    assert!(!l.contains(v(-1.0, 0.0)));
+    // The boundary, including vertices, counts as inside.
+    assert!(l.contains(v(1.0, 1.5)));
+    assert!(l.contains(v(2.0, 1.0)));
+    // A ray through a vertex must not be counted twice.
+    assert!(!l.contains(v(3.0, 1.0)));
+    assert!(l.contains(v(0.5, 1.0)));
+
+    // A pentagram winds twice around its center, and clockwise is negative.
+    let star: Vec<Vector<2>> = (0..5)
+        .map(|i| {
+            let angle = f64::from(i) * 4.0 * std::f64::consts::PI / 5.0;
+            v(angle.cos(), angle.sin())
+        })
+        .collect();
+    let star = Polygon::new(star);
+    assert_eq!(star.winding_number(v(0.0, 0.0)), 2);
+    let reversed = Polygon::new(star.vertices().iter().rev().copied().collect());
+    assert_eq!(reversed.winding_number(v(0.0, 0.0)), -2);
+    // A point in one of the tips is only wound around once.
+    assert_eq!(star.winding_number(v(0.9, 0.0)), 1);
+}
+
+#[test]
+fn test_convex_hull() {
+    let mut points = Vec::new();
+    for x in 0..5 {
+        for y in 0..4 {
+            points.push(v(f64::from(x), f64::from(y)));
+        }
+    }
+    // Duplicates and points on the edges are dropped.
+    points.push(v(2.0, 2.0));
+    let hull = Polygon::convex_hull(&points);
+    assert_eq!(
+        hull.vertices(),
+        [v(0.0, 0.0), v(4.0, 0.0), v(4.0, 3.0), v(0.0, 3.0)]
+    );
+    assert!(hull.signed_area() > 0.0);
+
+    let points = [
+        v(0.0, 0.0),
+        v(2.0, 1.0),
+        v(1.0, 5.0),
+        v(1.0, 1.0),
+        v(-1.0, 2.0),
+    ];
+    let hull = Polygon::convex_hull(&points);
+    assert_eq!(
+        hull.vertices(),
+        [v(-1.0, 2.0), v(0.0, 0.0), v(2.0, 1.0), v(1.0, 5.0)]
+    );
+    for &p in &points {
+        assert!(hull.contains(p));
+    }
+
+    assert_eq!(Polygon::convex_hull(&[]).vertices(), []);
+    assert_eq!(
+        Polygon::convex_hull(&[v(1.0, 1.0), v(1.0, 1.0)]).vertices(),
+        [v(1.0, 1.0)]
+    );
+    // Collinear points have a degenerate hull of their two ends.
+    assert_eq!(
+        Polygon::convex_hull(&[v(0.0, 0.0), v(2.0, 2.0), v(1.0, 1.0)]).vertices(),
+        [v(0.0, 0.0), v(2.0, 2.0)]
+    );
+}
@@ -0,0 +1,192 @@
+use std::ops::Mul;
+
+#[cfg(test)]
+use crate::vector::assert_close;
+use crate::{GeometryError, Vector};
+
+/// A unit quaternion `w + xi + yj + zk`, representing a rotation in three
+/// dimensions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    /// `[w, x, y, z]`, always of magnitude 1.
+    components: Vector<4>,
+}
+
+/// Above this dot product, `slerp` falls back to a normalized linear
+/// interpolation, as the sine of the angle between the two gets too small to
+/// divide by.
+const SLERP_LINEAR_THRESHOLD: f64 = 0.9995;
+
+impl Quaternion {
+    pub const IDENTITY: Self = Self {
+        components: Vector::new([1.0, 0.0, 0.0, 0.0]),
+    };
+
+    /// Normalize `w + xi + yj + zk` to a unit quaternion. Fails for zero or
+    /// non-finite components.
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Result<Self, GeometryError> {
+        Ok(Self {
+            components: Vector::new([w, x, y, z]).try_normalize()?,
+        })
+    }
+
+    /// The rotation by `angle` radians about `axis`. Fails if the axis has no
+    /// direction.
+    pub fn from_axis_angle(axis: Vector<3>, angle: f64) -> Result<Self, GeometryError> {
+        let axis = axis.try_normalize()?;
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Ok(Self {
+            components: Vector::new([cos, axis[0] * sin, axis[1] * sin, axis[2] * sin]),
+        })
+    }
+
+    /// `[w, x, y, z]`.
+    pub fn components(&self) -> [f64; 4] {
+        *self.components.coords()
+    }
+
+    /// The inverse rotation.
+    pub fn conjugate(self) -> Self {
+        let [w, x, y, z] = self.components();
+        Self {
+            components: Vector::new([w, -x, -y, -z]),
+        }
+    }
+
+    /// The rotation angle in radians, from 0 to pi.
+    pub fn angle(self) -> f64 {
+        let [w, x, y, z] = self.components();
+        2.0 * Vector::new([x, y, z]).magnitude().atan2(w.abs())
+    }
+
+    pub fn rotate(self, v: Vector<3>) -> Vector<3> {
+        // q * (0, v) * q^-1, expanded: v + 2w(u x v) + 2u x (u x v) with u
+        // the vector part.
+        let [w, x, y, z] = self.components();
+        let u = Vector::new([x, y, z]);
+        let t = u.cross(v) * 2.0;
+        v + t * w + u.cross(t)
+    }
+
+    /// Spherical linear interpolation: rotate from `self` at `t = 0` to
+    /// `other` at `t = 1` at constant angular speed, the short way around.
+    pub fn slerp(self, other: Self, t: f64) -> Self {
+        let mut dot = self.components.dot(other.components);
+        let mut other = other.components;
+        // q and -q are the same rotation; pick the closer one.
+        if dot < 0.0 {
+            dot = -dot;
+            other = -other;
+        }
+        if dot > SLERP_LINEAR_THRESHOLD {
+            return Self {
+                components: self.components.lerp(other, t).normalize(),
+            };
+        }
+        let theta = dot.acos();
+        let sin = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin;
+        let b = (t * theta).sin() / sin;
+        Self {
+            components: self.components * a + other * b,
+        }
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+
+    /// The Hamilton product: the rotation by `other` followed by `self`.
+    fn mul(self, other: Self) -> Self {
+        let [w1, x1, y1, z1] = self.components();
+        let [w2, x2, y2, z2] = other.components();
+        let product = Vector::new([
+            w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
+            w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+            w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+            w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+        ]);
+        // Renormalize so that rounding errors do not accumulate.
+        Self {
+            components: product.normalize(),
+        }
+    }
+}
+
+#[test]
+fn test_rotate() {
+    use std::f64::consts::FRAC_PI_2;
+    let z = Vector::new([0.0, 0.0, 3.0]);
+    let q = Quaternion::from_axis_angle(z, FRAC_PI_2).unwrap();
+    assert_close(q.rotate(Vector::new([1.0, 0.0, 0.0])), [0.0, 1.0, 0.0]);
+    assert_close(
+        q.conjugate().rotate(Vector::new([0.0, 1.0, 0.0])),
+        [1.0, 0.0, 0.0],
+    );
+    assert!((q.angle() - FRAC_PI_2).abs() < 1e-15);
+    assert_eq!(
+        Quaternion::from_axis_angle(Vector::ZERO, 1.0),
+        Err(GeometryError::ZeroVector)
+    );
+    assert_eq!(
+        Quaternion::new(2.0, 0.0, 0.0, 0.0),
+        Ok(Quaternion::IDENTITY)
+    );
+}
+
+#[test]
+fn test_matches_matrix() {
+    let q = Quaternion::new(0.3, -0.5, 0.7, 0.1).unwrap();
+    let m = crate::Matrix4::from(q);
+    for v in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, -2.0, 3.0]] {
+        let v = Vector::new(v);
+        assert_close(m.transform_vector(v), *q.rotate(v).coords());
+    }
+}
+
+#[test]
+fn test_compose() {
+    use std::f64::consts::FRAC_PI_2;
+    let x = Quaternion::from_axis_angle(Vector::new([1.0, 0.0, 0.0]), FRAC_PI_2).unwrap();
+    let z = Quaternion::from_axis_angle(Vector::new([0.0, 0.0, 1.0]), FRAC_PI_2).unwrap();
+    let v = Vector::new([0.0, 1.0, 0.0]);
+    // Rotating y about x gives z, and z about z stays put.
+    assert_close((z * x).rotate(v), [0.0, 0.0, 1.0]);
+    // Rotating y about z gives -x, and -x about x stays put.
+    assert_close((x * z).rotate(v), [-1.0, 0.0, 0.0]);
+    assert_close((x * x.conjugate()).rotate(v), [0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn test_slerp() {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+    let axis = Vector::new([0.0, 0.0, 1.0]);
+    let a = Quaternion::IDENTITY;
+    let b = Quaternion::from_axis_angle(axis, FRAC_PI_2).unwrap();
+    assert_eq!(a.slerp(b, 0.0), a);
+    assert!((a.slerp(b, 1.0).angle() - FRAC_PI_2).abs() < 1e-12);
+    // Constant angular speed.
+    for i in 0..=10 {
+        let t = f64::from(i) / 10.0;
+        assert!((a.slerp(b, t).angle() - t * FRAC_PI_2).abs() < 1e-12);
+    }
+    assert_close(
+        a.slerp(b, 0.5).rotate(Vector::new([1.0, 0.0, 0.0])),
+        [FRAC_PI_4.cos(), FRAC_PI_4.sin(), 0.0],
+    );
+    // -b is the same rotation, and slerp takes the short way to it as well.
+    let negated = Quaternion {
+        components: -b.components,
+    };
+    assert!((a.slerp(negated, 0.5).angle() - FRAC_PI_4).abs() < 1e-12);
+    // Nearly identical rotations interpolate linearly without dividing by 0.
+    let c = Quaternion::from_axis_angle(axis, 1e-9).unwrap();
+    let mid = a.slerp(c, 0.5);
+    assert!((mid.angle() - 0.5e-9).abs() < 1e-15);
+}
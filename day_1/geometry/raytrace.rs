@@ -0,0 +1,429 @@
+use std::io::{self, Write};
+
+use crate::{GeometryError, Matrix4, Vector};
+
+/// A linear RGB color with channels from 0 to 1, or a light intensity.
+pub type Color = Vector<3>;
+
+/// Offset of secondary rays from the surface they start on, so that they do
+/// not hit it again because of rounding.
+const SURFACE_OFFSET: f64 = 1e-9;
+
+/// A half-line from `origin` in the unit vector `direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vector<3>,
+    pub direction: Vector<3>,
+}
+
+impl Ray {
+    /// Fails if `direction` is the zero vector.
+    pub fn new(origin: Vector<3>, direction: Vector<3>) -> Result<Self, GeometryError> {
+        Ok(Self {
+            origin,
+            direction: direction.try_normalize()?,
+        })
+    }
+
+    pub fn at(&self, t: f64) -> Vector<3> {
+        self.origin + self.direction * t
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Sphere {
+        center: Vector<3>,
+        radius: f64,
+    },
+    /// The plane through `point`, perpendicular to the unit vector `normal`.
+    Plane {
+        point: Vector<3>,
+        normal: Vector<3>,
+    },
+}
+
+impl Shape {
+    /// The distance along `ray` to the nearest intersection in front of its
+    /// origin.
+    pub fn intersect(&self, ray: &Ray) -> Option<f64> {
+        match *self {
+            Shape::Sphere { center, radius } => {
+                // |o + td - c|^2 = r^2 with |d| = 1.
+                let offset = ray.origin - center;
+                let b = offset.dot(ray.direction);
+                let c = offset.dot(offset) - radius * radius;
+                let discriminant = b * b - c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                [-b - root, -b + root]
+                    .into_iter()
+                    .find(|&t| t > SURFACE_OFFSET)
+            }
+            Shape::Plane { point, normal } => {
+                let denominator = normal.dot(ray.direction);
+                if denominator == 0.0 {
+                    return None;
+                }
+                let t = (point - ray.origin).dot(normal) / denominator;
+                (t > SURFACE_OFFSET).then_some(t)
+            }
+        }
+    }
+
+    /// The outward unit normal at `point` on the surface.
+    pub fn normal_at(&self, point: Vector<3>) -> Vector<3> {
+        match *self {
+            Shape::Sphere { center, .. } => (point - center).normalize(),
+            Shape::Plane { normal, .. } => normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Material {
+    /// A matte surface that scatters light equally in all directions.
+    Lambertian { albedo: Color },
+    /// A reflective surface, tinting what it reflects.
+    Mirror { tint: Color },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Object {
+    pub shape: Shape,
+    pub material: Material,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    pub position: Vector<3>,
+    pub intensity: Color,
+}
+
+/// A pinhole camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    position: Vector<3>,
+    /// Maps directions relative to the camera, which looks down the
+    /// negative z axis, to directions in the scene.
+    to_scene: Matrix4,
+    focal_length: f64,
+}
+
+impl Camera {
+    /// A camera at `position` looking at `target`, with `up` pointing
+    /// upwards in the image. The image is 1 unit high at `focal_length` units
+    /// in front of the camera, so longer focal lengths zoom in.
+    ///
+    /// Fails if `position` and `target` coincide or `up` is parallel to the
+    /// view direction.
+    pub fn new(
+        position: Vector<3>,
+        target: Vector<3>,
+        up: Vector<3>,
+        focal_length: f64,
+    ) -> Result<Self, GeometryError> {
+        // The rotation part of the view transform is orthonormal, so its
+        // transpose undoes it. The translation moves into the bottom row,
+        // which `transform_vector` ignores.
+        let view = Matrix4::look_at(position, target, up)?;
+        Ok(Self {
+            position,
+            to_scene: view.transpose(),
+            focal_length,
+        })
+    }
+
+    /// The ray through the center of pixel `(x, y)`, counted from the top
+    /// left, of a `width` x `height` image.
+    pub fn ray(&self, x: usize, y: usize, width: usize, height: usize) -> Ray {
+        let pixel = 1.0 / height as f64;
+        let u = (x as f64 + 0.5) * pixel - 0.5 * width as f64 * pixel;
+        let v = 0.5 - (y as f64 + 0.5) * pixel;
+        let direction = self
+            .to_scene
+            .transform_vector(Vector::new([u, v, -self.focal_length]));
+        Ray {
+            origin: self.position,
+            direction: direction.normalize(),
+        }
+    }
+}
+
+/// Everything to render, lit by point lights and an ambient term.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub objects: Vec<Object>,
+    pub lights: Vec<PointLight>,
+    pub ambient: Color,
+    /// The color of rays that hit nothing.
+    pub background: Color,
+    /// How many times a ray may bounce between mirrors.
+    pub max_depth: u32,
+}
+
+/// The channel-wise product, for filtering light through a surface color.
+fn filter(a: Color, b: Color) -> Color {
+    Vector::new(std::array::from_fn(|i| a[i] * b[i]))
+}
+
+impl Scene {
+    /// The nearest object hit by `ray`, with the distance to it.
+    fn hit(&self, ray: &Ray) -> Option<(&Object, f64)> {
+        self.objects
+            .iter()
+            .filter_map(|object| Some((object, object.shape.intersect(ray)?)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// Whether anything blocks the way from `point` to `light`.
+    fn in_shadow(&self, point: Vector<3>, light: Vector<3>) -> bool {
+        let to_light = light - point;
+        let distance = to_light.magnitude();
+        let ray = Ray {
+            origin: point,
+            direction: to_light.normalize(),
+        };
+        self.objects
+            .iter()
+            .any(|object| object.shape.intersect(&ray).is_some_and(|t| t < distance))
+    }
+
+    /// The color seen along `ray`.
+    pub fn trace(&self, ray: &Ray, depth: u32) -> Color {
+        let Some((object, t)) = self.hit(ray) else {
+            return self.background;
+        };
+        let point = ray.at(t);
+        let mut normal = object.shape.normal_at(point);
+        // Shade the side facing the ray, which matters for planes.
+        if normal.dot(ray.direction) > 0.0 {
+            normal = -normal;
+        }
+        let surface = point + normal * SURFACE_OFFSET;
+        match object.material {
+            Material::Lambertian { albedo } => {
+                let mut light = self.ambient;
+                for source in &self.lights {
+                    if self.in_shadow(surface, source.position) {
+                        continue;
+                    }
+                    let cos = normal.dot((source.position - point).normalize());
+                    if cos > 0.0 {
+                        light = light + source.intensity * cos;
+                    }
+                }
+                filter(albedo, light)
+            }
+            Material::Mirror { tint } => {
+                if depth >= self.max_depth {
+                    return Vector::ZERO;
+                }
+                let d = ray.direction;
+                let reflected = Ray {
+                    origin: surface,
+                    direction: (d - normal * (2.0 * d.dot(normal))).normalize(),
+                };
+                filter(tint, self.trace(&reflected, depth + 1))
+            }
+        }
+    }
+
+    /// Render a `width` x `height` image with one ray through the center of
+    /// each pixel. The result only depends on correctly rounded IEEE 754
+    /// operations, so it is the same on every platform.
+    pub fn render(&self, camera: &Camera, width: usize, height: usize) -> Image {
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let color = self.trace(&camera.ray(x, y, width, height), 0);
+                pixels.push(color.coords().map(to_byte));
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Convert a linear channel value to 8 bits with a gamma of 2.
+fn to_byte(channel: f64) -> u8 {
+    (channel.clamp(0.0, 1.0).sqrt() * 255.0).round() as u8
+}
+
+/// An 8-bit RGB image, row by row from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Write as a binary PPM.
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "P6\n{} {}\n255", self.width, self.height)?;
+        out.write_all(self.pixels.as_flattened())
+    }
+}
+
+/// A red and a blue matte sphere and a mirror sphere on a grey floor, lit by
+/// two lights. Used by the golden-image test and the benchmark.
+pub fn demo_scene() -> (Scene, Camera) {
+    let sphere = |center, radius, material| Object {
+        shape: Shape::Sphere {
+            center: Vector::new(center),
+            radius,
+        },
+        material,
+    };
+    let matte = |r, g, b| Material::Lambertian {
+        albedo: Vector::new([r, g, b]),
+    };
+    let scene = Scene {
+        objects: vec![
+            Object {
+                shape: Shape::Plane {
+                    point: Vector::ZERO,
+                    normal: Vector::new([0.0, 1.0, 0.0]),
+                },
+                material: matte(0.8, 0.8, 0.8),
+            },
+            sphere([-1.5, 1.0, 0.0], 1.0, matte(0.9, 0.2, 0.2)),
+            sphere(
+                [0.75, 1.25, -1.0],
+                1.25,
+                Material::Mirror {
+                    tint: Vector::new([0.9, 0.9, 0.9]),
+                },
+            ),
+            sphere([2.0, 0.5, 1.25], 0.5, matte(0.2, 0.4, 0.9)),
+        ],
+        lights: vec![
+            PointLight {
+                position: Vector::new([-4.0, 6.0, 4.0]),
+                intensity: Vector::new([0.7, 0.7, 0.6]),
+            },
+            PointLight {
+                position: Vector::new([5.0, 3.0, 2.0]),
+                intensity: Vector::new([0.25, 0.25, 0.35]),
+            },
+        ],
+        ambient: Vector::new([0.1, 0.1, 0.1]),
+        background: Vector::new([0.5, 0.7, 1.0]),
+        max_depth: 4,
+    };
+    let camera = Camera::new(
+        Vector::new([0.0, 2.5, 7.0]),
+        Vector::new([0.0, 1.0, 0.0]),
+        Vector::new([0.0, 1.0, 0.0]),
+        1.2,
+    )
+    .unwrap();
+    (scene, camera)
+}
+
+#[test]
+fn test_sphere_intersection() {
+    let sphere = Shape::Sphere {
+        center: Vector::new([0.0, 0.0, -5.0]),
+        radius: 1.0,
+    };
+    let ray = Ray::new(Vector::ZERO, Vector::new([0.0, 0.0, -2.0])).unwrap();
+    assert_eq!(sphere.intersect(&ray), Some(4.0));
+    assert_eq!(ray.at(4.0), Vector::new([0.0, 0.0, -4.0]));
+    assert_eq!(sphere.normal_at(ray.at(4.0)), Vector::new([0.0, 0.0, 1.0]));
+    // From inside, the far side is hit.
+    let inside = Ray::new(Vector::new([0.0, 0.0, -5.0]), Vector::new([1.0, 0.0, 0.0])).unwrap();
+    assert_eq!(sphere.intersect(&inside), Some(1.0));
+    // Behind the origin or beside the sphere.
+    let away = Ray::new(Vector::ZERO, Vector::new([0.0, 0.0, 1.0])).unwrap();
+    assert_eq!(sphere.intersect(&away), None);
+    let beside = Ray::new(Vector::new([2.0, 0.0, 0.0]), Vector::new([0.0, 0.0, -1.0])).unwrap();
+    assert_eq!(sphere.intersect(&beside), None);
+    assert_eq!(
+        Ray::new(Vector::ZERO, Vector::ZERO),
+        Err(GeometryError::ZeroVector)
+    );
+}
+
+#[test]
+fn test_plane_intersection() {
+    let floor = Shape::Plane {
+        point: Vector::new([0.0, -1.0, 0.0]),
+        normal: Vector::new([0.0, 1.0, 0.0]),
+    };
+    let down = Ray::new(Vector::ZERO, Vector::new([0.0, -1.0, 0.0])).unwrap();
+    assert_eq!(floor.intersect(&down), Some(1.0));
+    let parallel = Ray::new(Vector::ZERO, Vector::new([1.0, 0.0, 0.0])).unwrap();
+    assert_eq!(floor.intersect(&parallel), None);
+    let up = Ray::new(Vector::ZERO, Vector::new([0.0, 1.0, 0.0])).unwrap();
+    assert_eq!(floor.intersect(&up), None);
+}
+
+#[test]
+fn test_shading() {
+    let light = PointLight {
+        position: Vector::new([0.0, 10.0, 0.0]),
+        intensity: Vector::new([1.0, 1.0, 1.0]),
+    };
+    let floor = Object {
+        shape: Shape::Plane {
+            point: Vector::ZERO,
+            normal: Vector::new([0.0, 1.0, 0.0]),
+        },
+        material: Material::Lambertian {
+            albedo: Vector::new([0.5, 0.25, 1.0]),
+        },
+    };
+    let mut scene = Scene {
+        objects: vec![floor],
+        lights: vec![light],
+        ambient: Vector::ZERO,
+        background: Vector::new([0.0, 0.0, 1.0]),
+        max_depth: 1,
+    };
+    let down = Ray::new(Vector::new([0.0, 1.0, 0.0]), Vector::new([0.0, -1.0, 0.0])).unwrap();
+    // Lit head-on, the color is the albedo.
+    assert_eq!(scene.trace(&down, 0), Vector::new([0.5, 0.25, 1.0]));
+    let up = Ray::new(Vector::new([0.0, 1.0, 0.0]), Vector::new([0.0, 1.0, 0.0])).unwrap();
+    assert_eq!(scene.trace(&up, 0), scene.background);
+
+    // A ball between the floor and the light casts a shadow.
+    scene.objects.push(Object {
+        shape: Shape::Sphere {
+            center: Vector::new([0.0, 5.0, 0.0]),
+            radius: 1.0,
+        },
+        material: floor.material,
+    });
+    let below_ball = Ray::new(Vector::new([0.0, 0.5, 0.0]), Vector::new([0.0, -1.0, 0.0])).unwrap();
+    assert_eq!(scene.trace(&below_ball, 0), Vector::ZERO);
+
+    // A mirror floor reflects the background, until the depth runs out.
+    scene.objects[0].material = Material::Mirror {
+        tint: Vector::new([0.5, 0.5, 0.5]),
+    };
+    scene.objects.pop();
+    assert_eq!(scene.trace(&down, 0), Vector::new([0.0, 0.0, 0.5]));
+    assert_eq!(scene.trace(&down, 1), Vector::ZERO);
+}
+
+#[test]
+fn test_write_ppm() {
+    let image = Image {
+        width: 2,
+        height: 1,
+        pixels: vec![[255, 0, 0], [0, 0, 255]],
+    };
+    let mut out = Vec::new();
+    image.write_ppm(&mut out).unwrap();
+    assert_eq!(out, b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff");
+    assert_eq!(to_byte(0.25), 128);
+    assert_eq!(to_byte(-1.0), 0);
+    assert_eq!(to_byte(2.0), 255);
+}
@@ -0,0 +1,45 @@
+//! Renders the demo scene and compares it byte for byte with the images in
+//! `tests/golden`. After an intended change to the renderer, regenerate them
+//! with `UPDATE_GOLDEN=1 cargo test -p geometry --test raytrace` and inspect
+//! the result.
+
+use geometry::raytrace::demo_scene;
+use std::fs;
+use std::path::PathBuf;
+
+fn check_golden(name: &str, width: usize, height: usize) {
+    let (scene, camera) = demo_scene();
+    let mut actual = Vec::new();
+    scene
+        .render(&camera, width, height)
+        .write_ppm(&mut actual)
+        .unwrap();
+
+    let golden = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read(&golden).unwrap();
+    if actual != expected {
+        let path = std::env::temp_dir().join(format!("raytrace-{}-{name}", std::process::id()));
+        fs::write(&path, &actual).unwrap();
+        panic!(
+            "{name} differs from {}, the render is in {}",
+            golden.display(),
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_golden_small() {
+    check_golden("demo-32x24.ppm", 32, 24);
+}
+
+#[test]
+fn test_golden_large() {
+    check_golden("demo-160x120.ppm", 160, 120);
+}
@@ -0,0 +1,303 @@
+use std::ops::Mul;
+
+#[cfg(test)]
+use crate::vector::assert_close;
+use crate::{GeometryError, Quaternion, Vector};
+
+/// A 4x4 matrix acting on homogeneous coordinates, where points have `w = 1`
+/// and directions have `w = 0`.
+///
+/// Vectors are columns, so `a * b` is the transform that applies `b` first
+/// and then `a`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    rows: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Self = Self::new([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    pub const fn new(rows: [[f64; 4]; 4]) -> Self {
+        Self { rows }
+    }
+
+    pub fn rows(&self) -> &[[f64; 4]; 4] {
+        &self.rows
+    }
+
+    /// Move points by `offset`. Directions are unaffected.
+    pub fn translation(offset: Vector<3>) -> Self {
+        let mut m = Self::IDENTITY;
+        for i in 0..3 {
+            m.rows[i][3] = offset[i];
+        }
+        m
+    }
+
+    /// Scale each axis by the corresponding factor.
+    pub fn scaling(factors: Vector<3>) -> Self {
+        let mut m = Self::IDENTITY;
+        for i in 0..3 {
+            m.rows[i][i] = factors[i];
+        }
+        m
+    }
+
+    /// Rotate by `angle` radians about `axis`, counterclockwise when looking
+    /// down the axis towards the origin. Fails if the axis has no direction.
+    pub fn rotation(axis: Vector<3>, angle: f64) -> Result<Self, GeometryError> {
+        Ok(Quaternion::from_axis_angle(axis, angle)?.into())
+    }
+
+    /// The view transform of a camera at `eye` looking at `target`: `eye`
+    /// moves to the origin, `target` onto the negative z axis and `up` into
+    /// the upper half of the yz plane.
+    ///
+    /// Fails if `eye` and `target` coincide or `up` is parallel to the view
+    /// direction.
+    pub fn look_at(
+        eye: Vector<3>,
+        target: Vector<3>,
+        up: Vector<3>,
+    ) -> Result<Self, GeometryError> {
+        let forward = (target - eye).try_normalize()?;
+        let right = forward.cross(up).try_normalize()?;
+        let up = right.cross(forward);
+        let mut m = Self::IDENTITY;
+        for (i, axis) in [right, up, -forward].into_iter().enumerate() {
+            for j in 0..3 {
+                m.rows[i][j] = axis[j];
+            }
+            m.rows[i][3] = -axis.dot(eye);
+        }
+        Ok(m)
+    }
+
+    /// A perspective projection with a vertical field of view of `fov_y`
+    /// radians, for a camera looking down the negative z axis. Points between
+    /// the `near` and `far` planes end up with `-1 <= z <= 1` after the
+    /// division by `w`.
+    pub fn perspective(
+        fov_y: f64,
+        aspect: f64,
+        near: f64,
+        far: f64,
+    ) -> Result<Self, GeometryError> {
+        if !(fov_y > 0.0 && fov_y < std::f64::consts::PI) {
+            return Err(GeometryError::InvalidProjection(
+                "the field of view must be between 0 and pi",
+            ));
+        }
+        if !(aspect > 0.0 && aspect.is_finite()) {
+            return Err(GeometryError::InvalidProjection(
+                "the aspect ratio must be positive and finite",
+            ));
+        }
+        if !(near > 0.0 && far > near) {
+            return Err(GeometryError::InvalidProjection(
+                "the planes must satisfy 0 < near < far",
+            ));
+        }
+        let f = 1.0 / (fov_y / 2.0).tan();
+        Ok(Self::new([
+            [f / aspect, 0.0, 0.0, 0.0],
+            [0.0, f, 0.0, 0.0],
+            [
+                0.0,
+                0.0,
+                (far + near) / (near - far),
+                2.0 * far * near / (near - far),
+            ],
+            [0.0, 0.0, -1.0, 0.0],
+        ]))
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.rows[j][i])
+        }))
+    }
+
+    /// Transform a point, dividing by the resulting `w`.
+    pub fn transform_point(&self, point: Vector<3>) -> Vector<3> {
+        let [x, y, z, w] = (*self * Vector::new([point[0], point[1], point[2], 1.0])).into();
+        Vector::new([x / w, y / w, z / w])
+    }
+
+    /// Transform a direction, which translations do not affect.
+    pub fn transform_vector(&self, vector: Vector<3>) -> Vector<3> {
+        let [x, y, z, _] = (*self * Vector::new([vector[0], vector[1], vector[2], 0.0])).into();
+        Vector::new([x, y, z])
+    }
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| (0..4).map(|k| self.rows[i][k] * other.rows[k][j]).sum())
+        }))
+    }
+}
+
+impl Mul<Vector<4>> for Matrix4 {
+    type Output = Vector<4>;
+
+    fn mul(self, v: Vector<4>) -> Vector<4> {
+        Vector::new(self.rows.map(|row| Vector::new(row).dot(v)))
+    }
+}
+
+impl From<Quaternion> for Matrix4 {
+    fn from(q: Quaternion) -> Self {
+        let [w, x, y, z] = q.components();
+        Self::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+#[test]
+fn test_translate_and_scale() {
+    let p = Vector::new([1.0, 2.0, 3.0]);
+    let translate = Matrix4::translation(Vector::new([10.0, 0.0, -1.0]));
+    let scale = Matrix4::scaling(Vector::new([2.0, 3.0, 4.0]));
+    assert_eq!(translate.transform_point(p), Vector::new([11.0, 2.0, 2.0]));
+    assert_eq!(translate.transform_vector(p), p);
+    assert_eq!(scale.transform_point(p), Vector::new([2.0, 6.0, 12.0]));
+    // The right factor applies first.
+    assert_eq!(
+        (translate * scale).transform_point(p),
+        Vector::new([12.0, 6.0, 11.0])
+    );
+    assert_eq!(
+        (scale * translate).transform_point(p),
+        Vector::new([22.0, 6.0, 8.0])
+    );
+    assert_eq!(Matrix4::IDENTITY * scale, scale);
+    assert_eq!(scale.transpose(), scale);
+    assert_eq!(translate.transpose().rows()[3], [10.0, 0.0, -1.0, 1.0]);
+}
+
+#[test]
+fn test_rotation() {
+    use std::f64::consts::FRAC_PI_2;
+    let z = Vector::new([0.0, 0.0, 1.0]);
+    let rotate = Matrix4::rotation(z, FRAC_PI_2).unwrap();
+    assert_close(
+        rotate.transform_point(Vector::new([1.0, 0.0, 5.0])),
+        [0.0, 1.0, 5.0],
+    );
+    // A third of a turn about the diagonal cycles the axes.
+    let diagonal = Vector::new([2.0, 2.0, 2.0]);
+    let rotate = Matrix4::rotation(diagonal, 2.0 * std::f64::consts::FRAC_PI_3).unwrap();
+    assert_close(
+        rotate.transform_vector(Vector::new([1.0, 0.0, 0.0])),
+        [0.0, 1.0, 0.0],
+    );
+    assert_close(
+        rotate.transform_vector(Vector::new([0.0, 1.0, 0.0])),
+        [0.0, 0.0, 1.0],
+    );
+    // Rotations are orthogonal.
+    let product = rotate * rotate.transpose();
+    for (row, expected) in product.rows().iter().zip(Matrix4::IDENTITY.rows()) {
+        for (a, e) in row.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-12);
+        }
+    }
+    assert_eq!(
+        Matrix4::rotation(Vector::ZERO, 1.0),
+        Err(GeometryError::ZeroVector)
+    );
+}
+
+#[test]
+fn test_look_at() {
+    let eye = Vector::new([1.0, 2.0, 3.0]);
+    let target = Vector::new([1.0, 2.0, -7.0]);
+    let up = Vector::new([0.0, 1.0, 0.0]);
+    let view = Matrix4::look_at(eye, target, up).unwrap();
+    assert_close(view.transform_point(eye), [0.0, 0.0, 0.0]);
+    assert_close(view.transform_point(target), [0.0, 0.0, -10.0]);
+    assert_close(view.transform_point(eye + up), [0.0, 1.0, 0.0]);
+
+    // Looking along x from the origin: x becomes -z and z becomes x.
+    let view = Matrix4::look_at(Vector::ZERO, Vector::new([5.0, 0.0, 0.0]), up).unwrap();
+    assert_close(
+        view.transform_vector(Vector::new([1.0, 0.0, 0.0])),
+        [0.0, 0.0, -1.0],
+    );
+    assert_close(
+        view.transform_vector(Vector::new([0.0, 0.0, 1.0])),
+        [1.0, 0.0, 0.0],
+    );
+
+    assert_eq!(
+        Matrix4::look_at(eye, eye, up),
+        Err(GeometryError::ZeroVector)
+    );
+    assert_eq!(
+        Matrix4::look_at(eye, eye + up, up),
+        Err(GeometryError::ZeroVector)
+    );
+}
+
+#[test]
+fn test_perspective() {
+    use std::f64::consts::FRAC_PI_2;
+    let project = Matrix4::perspective(FRAC_PI_2, 2.0, 1.0, 100.0).unwrap();
+    assert_close(
+        project.transform_point(Vector::new([0.0, 0.0, -1.0])),
+        [0.0, 0.0, -1.0],
+    );
+    assert_close(
+        project.transform_point(Vector::new([0.0, 0.0, -100.0])),
+        [0.0, 0.0, 1.0],
+    );
+    // The corners of the view frustum at distance 10.
+    let corner = project.transform_point(Vector::new([20.0, 10.0, -10.0]));
+    assert!((corner[0] - 1.0).abs() < 1e-12 && (corner[1] - 1.0).abs() < 1e-12);
+    assert!(matches!(
+        Matrix4::perspective(FRAC_PI_2, 1.0, 0.0, 10.0),
+        Err(GeometryError::InvalidProjection(_))
+    ));
+    assert!(matches!(
+        Matrix4::perspective(0.0, 1.0, 1.0, 10.0),
+        Err(GeometryError::InvalidProjection(_))
+    ));
+    assert!(matches!(
+        Matrix4::perspective(1.0, f64::NAN, 1.0, 10.0),
+        Err(GeometryError::InvalidProjection(_))
+    ));
+}
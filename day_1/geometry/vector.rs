@@ -0,0 +1,299 @@
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use crate::GeometryError;
+
+/// A vector in `N`-dimensional space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector<const N: usize> {
+    coords: [f64; N],
+}
+
+impl<const N: usize> Vector<N> {
+    pub const ZERO: Self = Self { coords: [0.0; N] };
+
+    pub const fn new(coords: [f64; N]) -> Self {
+        Self { coords }
+    }
+
+    pub fn coords(&self) -> &[f64; N] {
+        &self.coords
+    }
+
+    pub fn dot(self, other: Self) -> f64 {
+        self.coords
+            .iter()
+            .zip(other.coords)
+            .map(|(a, b)| a * b)
+            .sum()
+    }
+
+    /// The euclidean length.
+    ///
+    /// Like `f64::hypot`, the coordinates are scaled by the largest of them
+    /// before squaring, so the result only overflows or underflows if the
+    /// length itself is out of range.
+    pub fn magnitude(self) -> f64 {
+        let largest = self.largest();
+        if largest == 0.0 || largest.is_infinite() {
+            return largest;
+        }
+        let scaled = self.map(|x| x / largest);
+        largest * scaled.dot(scaled).sqrt()
+    }
+
+    /// The vector with the same direction and a magnitude of 1. The zero
+    /// vector has no direction, so its coordinates become NaN; use
+    /// `try_normalize` to catch that.
+    pub fn normalize(self) -> Self {
+        // Scaling first keeps vectors longer than `f64::MAX` finite.
+        let largest = self.largest();
+        let scaled = self.map(|x| x / largest);
+        let magnitude = scaled.dot(scaled).sqrt();
+        scaled.map(|x| x / magnitude)
+    }
+
+    /// Like `normalize`, but fails for the zero vector and for vectors with
+    /// infinite or NaN coordinates.
+    pub fn try_normalize(self) -> Result<Self, GeometryError> {
+        if !self.coords.iter().all(|x| x.is_finite()) {
+            return Err(GeometryError::NonFinite);
+        }
+        if self.largest() == 0.0 {
+            return Err(GeometryError::ZeroVector);
+        }
+        Ok(self.normalize())
+    }
+
+    pub fn distance(self, other: Self) -> f64 {
+        (other - self).magnitude()
+    }
+
+    /// Linear interpolation: `self` at `t = 0` and `other` at `t = 1`.
+    pub fn lerp(self, other: Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+
+    /// The angle between the two vectors in radians, from 0 to pi. NaN if
+    /// either of them is the zero vector.
+    pub fn angle(self, other: Self) -> f64 {
+        // Kahan's formula. The arccosine of the normalized dot product loses
+        // half of the digits for nearly (anti)parallel vectors.
+        let a = self.normalize();
+        let b = other.normalize();
+        2.0 * (a - b).magnitude().atan2((a + b).magnitude())
+    }
+
+    /// The largest absolute value of the coordinates, or 0 for `N = 0`. NaN
+    /// if any of them is NaN, unlike with `f64::max`.
+    fn largest(self) -> f64 {
+        self.coords
+            .iter()
+            .map(|x| x.abs())
+            .fold(0.0, |max, x| if x > max || x.is_nan() { x } else { max })
+    }
+
+    fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Self::new(self.coords.map(f))
+    }
+
+    fn zip(self, other: Self, f: impl Fn(f64, f64) -> f64) -> Self {
+        Self::new(std::array::from_fn(|i| f(self.coords[i], other.coords[i])))
+    }
+}
+
+impl Vector<3> {
+    /// The cross product, perpendicular to both vectors.
+    pub fn cross(self, other: Self) -> Self {
+        let [a1, a2, a3] = self.coords;
+        let [b1, b2, b3] = other.coords;
+        Self::new([a2 * b3 - a3 * b2, a3 * b1 - a1 * b3, a1 * b2 - a2 * b1])
+    }
+}
+
+impl<const N: usize> Default for Vector<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const N: usize> From<[f64; N]> for Vector<N> {
+    fn from(coords: [f64; N]) -> Self {
+        Self::new(coords)
+    }
+}
+
+impl<const N: usize> From<Vector<N>> for [f64; N] {
+    fn from(v: Vector<N>) -> Self {
+        v.coords
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = f64;
+
+    fn index(&self, i: usize) -> &f64 {
+        &self.coords[i]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Vector<N> {
+    fn index_mut(&mut self, i: usize) -> &mut f64 {
+        &mut self.coords[i]
+    }
+}
+
+impl<const N: usize> Add for Vector<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip(other, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> Sub for Vector<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip(other, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> Mul<f64> for Vector<N> {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self {
+        self.map(|x| x * factor)
+    }
+}
+
+impl<const N: usize> Neg for Vector<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|x| -x)
+    }
+}
+
+/// Compare coordinates to within rounding error. Shared by the tests of
+/// the other modules.
+#[cfg(test)]
+pub(crate) fn assert_close<const N: usize>(actual: Vector<N>, expected: [f64; N]) {
+    for (a, e) in actual.coords.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-12, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn test_operators() {
+    let a = Vector::new([1.0, 2.0]);
+    let b = Vector::new([3.0, -1.0]);
+    assert_eq!(a + b, Vector::new([4.0, 1.0]));
+    assert_eq!(a - b, Vector::new([-2.0, 3.0]));
+    assert_eq!(a * 2.0, Vector::new([2.0, 4.0]));
+    assert_eq!(-a, Vector::new([-1.0, -2.0]));
+    assert_eq!(a + Vector::ZERO, a);
+    assert_eq!(a[1], 2.0);
+    let mut c = a;
+    c[0] = 5.0;
+    assert_eq!(<[f64; 2]>::from(c), [5.0, 2.0]);
+}
+
+#[test]
+fn test_products() {
+    let x = Vector::new([1.0, 0.0, 0.0]);
+    let y = Vector::new([0.0, 1.0, 0.0]);
+    let z = Vector::new([0.0, 0.0, 1.0]);
+    assert_eq!(x.cross(y), z);
+    assert_eq!(y.cross(z), x);
+    assert_eq!(y.cross(x), -z);
+    let a = Vector::new([1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(a.dot(a), 30.0);
+    let b = Vector::new([2.0, -1.0, 5.0]);
+    let c = Vector::new([0.5, 4.0, 1.0]);
+    // The cross product is perpendicular to both factors.
+    assert_eq!(b.cross(c).dot(b), 0.0);
+    assert_eq!(b.cross(c).dot(c), 0.0);
+}
+
+#[test]
+fn test_metrics() {
+    let a = Vector::new([1.0, 1.0]);
+    let b = Vector::new([4.0, 5.0]);
+    assert_eq!(a.distance(b), 5.0);
+    assert_eq!(a.lerp(b, 0.0), a);
+    assert_eq!(a.lerp(b, 1.0), b);
+    assert_eq!(a.lerp(b, 0.5), Vector::new([2.5, 3.0]));
+    assert_close(Vector::new([0.0, 3.0, 4.0]).normalize(), [0.0, 0.6, 0.8]);
+    assert!(Vector::<3>::ZERO.normalize()[0].is_nan());
+}
+
+#[test]
+fn test_angle() {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+    let x = Vector::new([1.0, 0.0]);
+    assert_eq!(x.angle(Vector::new([0.0, 3.0])), FRAC_PI_2);
+    assert!((x.angle(Vector::new([2.0, 2.0])) - FRAC_PI_4).abs() < 1e-15);
+    assert_eq!(x.angle(-x), PI);
+    // Parallel vectors come out within rounding error of 0, not within its
+    // square root.
+    let v = Vector::new([0.1, 0.2, 0.3]);
+    assert!(v.angle(v * 3.0) < 1e-15);
+    assert!(x.angle(Vector::ZERO).is_nan());
+}
+
+#[cfg(test)]
+fn assert_relative(actual: f64, expected: f64) {
+    assert!(
+        ((actual - expected) / expected).abs() < 1e-15,
+        "{actual} != {expected}"
+    );
+}
+
+#[test]
+fn test_extreme_magnitudes() {
+    // Squaring any of these coordinates would overflow or underflow.
+    assert_relative(Vector::new([3e200, 4e200]).magnitude(), 5e200);
+    assert_relative(Vector::new([3e-200, -4e-200]).magnitude(), 5e-200);
+    assert_relative(Vector::new([1e200, 1e200, 1e200, 1e200]).magnitude(), 2e200);
+    assert_relative(Vector::new([3e-320, 4e-320]).magnitude(), 5e-320);
+    assert_eq!(Vector::new([1e300, 1e-300]).magnitude(), 1e300);
+    // Only a length beyond `f64::MAX` overflows.
+    assert_eq!(Vector::new([f64::MAX, f64::MAX]).magnitude(), f64::INFINITY);
+    assert_eq!(Vector::new([f64::INFINITY, 1.0]).magnitude(), f64::INFINITY);
+    assert!(Vector::new([f64::NAN, 1.0]).magnitude().is_nan());
+    assert!(Vector::new([1.0, f64::NAN]).magnitude().is_nan());
+    assert_eq!(Vector::<0>::ZERO.magnitude(), 0.0);
+
+    assert_close(Vector::new([3e200, 4e200]).normalize(), [0.6, 0.8]);
+    assert_close(Vector::new([3e-320, 4e-320]).normalize(), [0.6, 0.8]);
+    let half = 0.5f64.sqrt();
+    assert_close(
+        Vector::new([f64::MAX, -f64::MAX]).normalize(),
+        [half, -half],
+    );
+}
+
+#[test]
+fn test_try_normalize() {
+    assert_eq!(
+        Vector::new([0.0, 3.0, 4.0]).try_normalize(),
+        Ok(Vector::new([0.0, 0.6, 0.8]))
+    );
+    assert_eq!(
+        Vector::<3>::ZERO.try_normalize(),
+        Err(GeometryError::ZeroVector)
+    );
+    assert_eq!(
+        Vector::new([-0.0, 0.0]).try_normalize(),
+        Err(GeometryError::ZeroVector)
+    );
+    assert_eq!(
+        Vector::new([1.0, f64::INFINITY]).try_normalize(),
+        Err(GeometryError::NonFinite)
+    );
+    assert_eq!(
+        Vector::new([f64::NAN, 0.0]).try_normalize(),
+        Err(GeometryError::NonFinite)
+    );
+    assert!(Vector::new([1e-310, 0.0]).try_normalize().is_ok());
+}
@@ -0,0 +1,59 @@
+//! Compares the transpose implementations on large matrices.
+//!
+//! Run with `cargo bench -p nested_arrays`. Pass a size to override the
+//! default shapes, e.g. `cargo bench -p nested_arrays -- 8192`.
+
+use nested_arrays::{transpose_blocked, transpose_naive, transpose_slice_in_place};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 5;
+
+/// The fastest of `RUNS` runs, which is the least disturbed by other load.
+fn time(mut f: impl FnMut()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn bench(rows: usize, cols: usize) {
+    let src: Vec<f64> = (0..rows * cols).map(|i| i as f64).collect();
+    let mut dst = vec![0.0; src.len()];
+
+    let naive = time(|| transpose_naive(black_box(&src), rows, cols, &mut dst));
+    let blocked = time(|| transpose_blocked(black_box(&src), rows, cols, &mut dst));
+    // Each run transposes the previous result, so swap the shape to match.
+    let mut data = src.clone();
+    let mut shape = (rows, cols);
+    let in_place = time(|| {
+        transpose_slice_in_place(black_box(&mut data), shape.0, shape.1);
+        shape = (shape.1, shape.0);
+    });
+
+    println!(
+        "{rows:>5}x{cols:<5}  naive {naive:>10.2?}  blocked {blocked:>10.2?} ({:.1}x)  in place {in_place:>10.2?}",
+        naive.as_secs_f64() / blocked.as_secs_f64()
+    );
+}
+
+fn main() {
+    let sizes: Vec<usize> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| arg.parse().ok())
+        .collect();
+    if sizes.is_empty() {
+        bench(1024, 1024);
+        bench(4096, 4096);
+        bench(8192, 8192);
+        bench(3000, 7000);
+    } else {
+        for size in sizes {
+            bench(size, size);
+        }
+    }
+}
@@ -0,0 +1,631 @@
+use std::fmt;
+
+use crate::{Matrix, MatrixError};
+
+/// An LU decomposition with partial pivoting, `P * A = L * U`, of a square
+/// `f64` matrix `A`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lu {
+    /// `L` below the diagonal (its unit diagonal is implied) and `U` on and
+    /// above it.
+    lu: Matrix<f64>,
+    /// Row `i` of `P * A` is row `perm[i]` of `A`.
+    perm: Vec<usize>,
+    /// The determinant of `P`.
+    sign: f64,
+}
+
+/// Gaussian elimination with partial pivoting that keeps a first-order bound
+/// on the rounding error of every entry, to tell pivots that are really 0
+/// from those that are merely small.
+///
+/// A fixed tolerance relative to the input does not work: the error in a
+/// pivot grows with the entries of the reduced matrix and with how badly
+/// conditioned the rows above it are.
+struct Reduction {
+    a: Matrix<f64>,
+    error: Matrix<f64>,
+}
+
+impl Reduction {
+    fn new(a: &Matrix<f64>) -> Self {
+        Self {
+            a: a.clone(),
+            error: Matrix::zeros(a.rows(), a.cols()),
+        }
+    }
+
+    /// The row from `start` down with the largest entry in `col`, unless that
+    /// entry is within its rounding error of 0.
+    fn pivot_row(&self, start: usize, col: usize) -> Option<usize> {
+        let row = (start..self.a.rows())
+            .max_by(|&i, &j| self.a[(i, col)].abs().total_cmp(&self.a[(j, col)].abs()))?;
+        (self.a[(row, col)].abs() > self.error[(row, col)]).then_some(row)
+    }
+
+    fn swap_rows(&mut self, i: usize, j: usize) {
+        swap_rows(&mut self.a, i, j);
+        swap_rows(&mut self.error, i, j);
+    }
+
+    /// Subtract multiples of row `row` from the rows below it to clear `col`
+    /// under the pivot, and store the multiples in their place.
+    fn eliminate(&mut self, row: usize, col: usize) {
+        let (a, error) = (&mut self.a, &mut self.error);
+        let pivot = a[(row, col)];
+        for i in row + 1..a.rows() {
+            let factor = a[(i, col)] / pivot;
+            let factor_error = (error[(i, col)] + factor.abs() * error[(row, col)]) / pivot.abs()
+                + f64::EPSILON * factor.abs();
+            a[(i, col)] = factor;
+            error[(i, col)] = factor_error;
+            for j in col + 1..a.cols() {
+                let product = factor * a[(row, j)];
+                let difference = a[(i, j)] - product;
+                error[(i, j)] += factor.abs() * error[(row, j)]
+                    + factor_error * a[(row, j)].abs()
+                    + f64::EPSILON * (difference.abs() + product.abs());
+                a[(i, j)] = difference;
+            }
+        }
+    }
+}
+
+impl Lu {
+    fn new(a: &Matrix<f64>) -> Result<Self, MatrixError> {
+        let n = square_size(a)?;
+        let mut reduction = Reduction::new(a);
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut sign = 1.0;
+        for k in 0..n {
+            let pivot_row = reduction.pivot_row(k, k).ok_or(MatrixError::Singular)?;
+            if pivot_row != k {
+                reduction.swap_rows(k, pivot_row);
+                perm.swap(k, pivot_row);
+                sign = -sign;
+            }
+            reduction.eliminate(k, k);
+        }
+        Ok(Self {
+            lu: reduction.a,
+            perm,
+            sign,
+        })
+    }
+
+    /// The unit lower triangular factor `L`.
+    pub fn lower(&self) -> Matrix<f64> {
+        let n = self.lu.rows();
+        let mut lower = Matrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                lower[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        lower
+    }
+
+    /// The upper triangular factor `U`.
+    pub fn upper(&self) -> Matrix<f64> {
+        let n = self.lu.rows();
+        let mut upper = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                upper[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        upper
+    }
+
+    /// The row permutation: row `i` of `P * A` is row `permutation()[i]` of
+    /// `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn determinant(&self) -> f64 {
+        (0..self.lu.rows()).fold(self.sign, |det, i| det * self.lu[(i, i)])
+    }
+
+    /// Solve `A * x = b` by forward and back substitution.
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        let n = self.lu.rows();
+        if b.len() != n {
+            return Err(MatrixError::DimensionMismatch {
+                op: "solve",
+                left: self.lu.dims(),
+                right: (b.len(), 1),
+            });
+        }
+        let mut x: Vec<f64> = self.perm.iter().map(|&i| b[i]).collect();
+        // L * y = P * b
+        for i in 0..n {
+            for j in 0..i {
+                x[i] -= self.lu[(i, j)] * x[j];
+            }
+        }
+        // U * x = y
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] -= self.lu[(i, j)] * x[j];
+            }
+            x[i] /= self.lu[(i, i)];
+        }
+        Ok(x)
+    }
+
+    /// Solve for every column of the identity.
+    pub fn inverse(&self) -> Matrix<f64> {
+        let n = self.lu.rows();
+        let mut inverse = Matrix::zeros(n, n);
+        let mut e = vec![0.0; n];
+        for j in 0..n {
+            e[j] = 1.0;
+            let column = self
+                .solve(&e)
+                .expect("the unit vector has the right length");
+            e[j] = 0.0;
+            for (i, x) in column.into_iter().enumerate() {
+                inverse[(i, j)] = x;
+            }
+        }
+        inverse
+    }
+}
+
+impl Matrix<f64> {
+    /// Decompose into `P * A = L * U`. Fails if the matrix is not square or
+    /// is singular, up to rounding.
+    pub fn lu(&self) -> Result<Lu, MatrixError> {
+        Lu::new(self)
+    }
+
+    /// The determinant, which is 0 for a singular matrix.
+    pub fn determinant(&self) -> Result<f64, MatrixError> {
+        match Lu::new(self) {
+            Ok(lu) => Ok(lu.determinant()),
+            Err(MatrixError::Singular) => Ok(0.0),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn inverse(&self) -> Result<Self, MatrixError> {
+        Ok(Lu::new(self)?.inverse())
+    }
+
+    /// Solve `A * x = b` for `x`.
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        Lu::new(self)?.solve(b)
+    }
+
+    /// The number of linearly independent rows, with pivots that are within
+    /// their rounding error of 0 counted as 0.
+    pub fn rank(&self) -> usize {
+        let mut reduction = Reduction::new(self);
+        let mut rank = 0;
+        for col in 0..self.cols() {
+            if let Some(pivot_row) = reduction.pivot_row(rank, col) {
+                reduction.swap_rows(rank, pivot_row);
+                reduction.eliminate(rank, col);
+                rank += 1;
+            }
+        }
+        rank
+    }
+}
+
+/// An exact rational number, always in lowest terms with a positive
+/// denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fraction {
+    numer: i128,
+    denom: i128,
+}
+
+impl Fraction {
+    /// Panics if `denom` is 0.
+    pub fn new(numer: i128, denom: i128) -> Self {
+        assert!(denom != 0, "fraction with a zero denominator");
+        let gcd = gcd(numer.unsigned_abs(), denom.unsigned_abs()) as i128;
+        let sign = denom.signum();
+        Self {
+            numer: sign * numer / gcd,
+            denom: sign * denom / gcd,
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numer as f64 / self.denom as f64
+    }
+}
+
+impl From<i128> for Fraction {
+    fn from(n: i128) -> Self {
+        Self { numer: n, denom: 1 }
+    }
+}
+
+impl fmt::Display for Fraction {
+    /// `numer/denom`, or just `numer` for whole numbers.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = if self.denom == 1 {
+            self.numer.to_string()
+        } else {
+            format!("{}/{}", self.numer, self.denom)
+        };
+        f.pad(&text)
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn square_size<T>(a: &Matrix<T>) -> Result<usize, MatrixError> {
+    if a.is_square() {
+        Ok(a.rows())
+    } else {
+        Err(MatrixError::NotSquare {
+            rows: a.rows(),
+            cols: a.cols(),
+        })
+    }
+}
+
+fn swap_rows<T>(a: &mut Matrix<T>, i: usize, j: usize) {
+    let cols = a.cols();
+    for k in 0..cols {
+        a.as_mut_slice().swap(i * cols + k, j * cols + k);
+    }
+}
+
+/// One step of Bareiss' algorithm: `(pivot * x - factor * y) / previous`.
+/// Sylvester's identity guarantees that the division is exact.
+fn bareiss_step(
+    pivot: i128,
+    x: i128,
+    factor: i128,
+    y: i128,
+    previous: i128,
+) -> Result<i128, MatrixError> {
+    pivot
+        .checked_mul(x)
+        .zip(factor.checked_mul(y))
+        .and_then(|(a, b)| a.checked_sub(b))
+        .map(|n| n / previous)
+        .ok_or(MatrixError::Overflow)
+}
+
+/// The outcome of fraction-free forward elimination.
+struct Echelon {
+    rank: usize,
+    /// The last pivot, which for a square matrix of full rank is its
+    /// determinant up to sign.
+    pivot: i128,
+    /// The determinant of the row permutation.
+    sign: i128,
+}
+
+/// Reduce `a` to row echelon form with Bareiss' algorithm. Every
+/// intermediate value is a minor of the original matrix, so nothing is
+/// rounded and the values grow only as much as the determinant does.
+fn bareiss(mut a: Matrix<i128>) -> Result<Echelon, MatrixError> {
+    let (rows, cols) = a.dims();
+    let mut echelon = Echelon {
+        rank: 0,
+        pivot: 1,
+        sign: 1,
+    };
+    for col in 0..cols {
+        let r = echelon.rank;
+        if r == rows {
+            break;
+        }
+        let Some(pivot_row) = (r..rows).find(|&i| a[(i, col)] != 0) else {
+            continue;
+        };
+        if pivot_row != r {
+            swap_rows(&mut a, r, pivot_row);
+            echelon.sign = -echelon.sign;
+        }
+        let pivot = a[(r, col)];
+        for i in r + 1..rows {
+            let factor = a[(i, col)];
+            for j in col + 1..cols {
+                a[(i, j)] = bareiss_step(pivot, a[(i, j)], factor, a[(r, j)], echelon.pivot)?;
+            }
+            a[(i, col)] = 0;
+        }
+        echelon.pivot = pivot;
+        echelon.rank += 1;
+    }
+    Ok(echelon)
+}
+
+/// Fraction-free Gauss-Jordan elimination of `[A | B]`, where `A` is the
+/// leading square block. Afterwards `A` is `d` times the identity, where `d`
+/// is the returned last pivot, and the solution of `A * X = B` is `B / d`.
+fn bareiss_jordan(a: &mut Matrix<i128>) -> Result<i128, MatrixError> {
+    let (n, cols) = a.dims();
+    let mut previous = 1;
+    for k in 0..n {
+        let pivot_row = (k..n)
+            .find(|&i| a[(i, k)] != 0)
+            .ok_or(MatrixError::Singular)?;
+        swap_rows(a, k, pivot_row);
+        let pivot = a[(k, k)];
+        for i in (0..n).filter(|&i| i != k) {
+            let factor = a[(i, k)];
+            for j in (0..cols).filter(|&j| j != k) {
+                a[(i, j)] = bareiss_step(pivot, a[(i, j)], factor, a[(k, j)], previous)?;
+            }
+            a[(i, k)] = 0;
+        }
+        previous = pivot;
+    }
+    Ok(previous)
+}
+
+macro_rules! impl_exact {
+    ($($t:ty),*) => {
+        $(
+            impl Matrix<$t> {
+                /// The exact determinant, computed without fractions.
+                /// Fails if an intermediate value overflows an `i128`.
+                pub fn determinant(&self) -> Result<i128, MatrixError> {
+                    let n = square_size(self)?;
+                    let echelon = bareiss(self.widen())?;
+                    Ok(if echelon.rank < n {
+                        0
+                    } else {
+                        echelon.sign * echelon.pivot
+                    })
+                }
+
+                /// The exact inverse.
+                pub fn inverse(&self) -> Result<Matrix<Fraction>, MatrixError> {
+                    let n = square_size(self)?;
+                    let mut a = Matrix::zeros(n, 2 * n);
+                    for i in 0..n {
+                        for j in 0..n {
+                            a[(i, j)] = i128::from(self[(i, j)]);
+                        }
+                        a[(i, n + i)] = 1;
+                    }
+                    let d = bareiss_jordan(&mut a)?;
+                    let data = (0..n)
+                        .flat_map(|i| (0..n).map(move |j| (i, j)))
+                        .map(|(i, j)| Fraction::new(a[(i, n + j)], d))
+                        .collect();
+                    Matrix::from_vec(n, n, data)
+                }
+
+                /// Solve `A * x = b` exactly.
+                pub fn solve(&self, b: &[$t]) -> Result<Vec<Fraction>, MatrixError> {
+                    let n = square_size(self)?;
+                    if b.len() != n {
+                        return Err(MatrixError::DimensionMismatch {
+                            op: "solve",
+                            left: self.dims(),
+                            right: (b.len(), 1),
+                        });
+                    }
+                    let mut a = Matrix::zeros(n, n + 1);
+                    for i in 0..n {
+                        for j in 0..n {
+                            a[(i, j)] = i128::from(self[(i, j)]);
+                        }
+                        a[(i, n)] = i128::from(b[i]);
+                    }
+                    let d = bareiss_jordan(&mut a)?;
+                    Ok((0..n).map(|i| Fraction::new(a[(i, n)], d)).collect())
+                }
+
+                /// The exact number of linearly independent rows.
+                pub fn rank(&self) -> Result<usize, MatrixError> {
+                    Ok(bareiss(self.widen())?.rank)
+                }
+
+                fn widen(&self) -> Matrix<i128> {
+                    let data = self.as_slice().iter().map(|&x| i128::from(x)).collect();
+                    Matrix::from_vec(self.rows(), self.cols(), data).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+impl_exact!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+#[cfg(test)]
+fn assert_close(actual: &Matrix<f64>, expected: &Matrix<f64>) {
+    assert_eq!(actual.dims(), expected.dims());
+    for (a, e) in actual.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - e).abs() < 1e-9, "{actual} != {expected}");
+    }
+}
+
+#[test]
+fn test_lu_factors() {
+    let a = Matrix::from([[2.0, 1.0, 1.0], [4.0, -6.0, 0.0], [-2.0, 7.0, 2.0]]);
+    let lu = a.lu().unwrap();
+    let mut permuted = Matrix::zeros(3, 3);
+    for (i, &row) in lu.permutation().iter().enumerate() {
+        for j in 0..3 {
+            permuted[(i, j)] = a[(row, j)];
+        }
+    }
+    assert_close(&(&lu.lower() * &lu.upper()).unwrap(), &permuted);
+    // The largest element of the first column becomes the first pivot.
+    assert_eq!(lu.permutation()[0], 1);
+    assert!((lu.determinant() - -16.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_float_inverse_and_solve() {
+    let a = Matrix::from([[4.0, 7.0], [2.0, 6.0]]);
+    let inverse = a.inverse().unwrap();
+    assert_close(&inverse, &Matrix::from([[0.6, -0.7], [-0.2, 0.4]]));
+    assert_close(&(&a * &inverse).unwrap(), &Matrix::identity(2));
+
+    // A zero in the top left needs a row swap.
+    let a = Matrix::from([[0.0, 2.0, 1.0], [1.0, 1.0, 1.0], [2.0, 1.0, 0.0]]);
+    let x = a.solve(&[5.0, 4.0, 4.0]).unwrap();
+    for (x, expected) in x.iter().zip([1.0, 2.0, 1.0]) {
+        assert!((x - expected).abs() < 1e-12);
+    }
+    assert_eq!(
+        a.solve(&[1.0, 2.0]),
+        Err(MatrixError::DimensionMismatch {
+            op: "solve",
+            left: (3, 3),
+            right: (2, 1)
+        })
+    );
+}
+
+#[test]
+fn test_float_singular() {
+    let a = Matrix::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    assert_eq!(a.inverse(), Err(MatrixError::Singular));
+    assert_eq!(a.solve(&[1.0, 2.0, 3.0]), Err(MatrixError::Singular));
+    assert_eq!(a.determinant(), Ok(0.0));
+    assert_eq!(a.rank(), 2);
+    assert_eq!(Matrix::<f64>::zeros(2, 3).rank(), 0);
+    assert_eq!(
+        Matrix::<f64>::zeros(2, 3).determinant(),
+        Err(MatrixError::NotSquare { rows: 2, cols: 3 })
+    );
+
+    // Of exact rank 3, but the last pivot only cancels to about 1e-14, more
+    // than `n * EPSILON` times the largest entry.
+    let rows = [
+        [8, -7, -2, 8],
+        [-9, 10, -3, 0],
+        [13, -12, -5, -2],
+        [-1, 0, 1, -10],
+    ];
+    assert_eq!(Matrix::<i32>::from(rows).determinant(), Ok(0));
+    let a = Matrix::from(rows.map(|row| row.map(f64::from)));
+    assert_eq!(a.inverse(), Err(MatrixError::Singular));
+    assert_eq!(a.solve(&[1.0; 4]), Err(MatrixError::Singular));
+    assert_eq!(a.determinant(), Ok(0.0));
+    assert_eq!(a.rank(), 3);
+}
+
+#[test]
+fn test_exact_determinant() {
+    let a: Matrix<i64> = Matrix::from([[2, -3, 1], [2, 0, -1], [1, 4, 5]]);
+    assert_eq!(a.determinant(), Ok(49));
+    assert_eq!(Matrix::<i32>::identity(5).determinant(), Ok(1));
+    // Swapping two rows flips the sign.
+    let a: Matrix<i64> = Matrix::from([[0, 1], [1, 0]]);
+    assert_eq!(a.determinant(), Ok(-1));
+    let a: Matrix<i64> = Matrix::from([[1, 2], [2, 4]]);
+    assert_eq!(a.determinant(), Ok(0));
+
+    // The Hilbert matrix scaled by lcm(1..=11) = 27720 to be integral has
+    // a determinant that `f64` cannot represent exactly.
+    let n = 6;
+    let mut hilbert = Matrix::<i64>::zeros(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            hilbert[(i, j)] = 27720 / (i + j + 1) as i64;
+        }
+    }
+    // det(H_6) = 1 / 186313420339200000, times 27720^6.
+    let expected = 27720i128.pow(6) / 186_313_420_339_200_000;
+    assert_eq!(hilbert.determinant(), Ok(expected));
+}
+
+#[test]
+fn test_exact_inverse_and_solve() {
+    let a: Matrix<i64> = Matrix::from([[2, 1], [1, 3]]);
+    let inverse = a.inverse().unwrap();
+    assert_eq!(
+        inverse,
+        Matrix::from([
+            [Fraction::new(3, 5), Fraction::new(-1, 5)],
+            [Fraction::new(-1, 5), Fraction::new(2, 5)]
+        ])
+    );
+    assert_eq!(inverse.to_string(), "[ 3/5  -1/5]\n[-1/5   2/5]\n");
+    assert_eq!(
+        a.solve(&[3, 5]),
+        Ok(vec![Fraction::new(4, 5), Fraction::new(7, 5)])
+    );
+
+    let a: Matrix<i32> = Matrix::from([[0, 2, 1], [1, 1, 1], [2, 1, 0]]);
+    assert_eq!(
+        a.solve(&[5, 4, 4]),
+        Ok(vec![
+            Fraction::from(1),
+            Fraction::from(2),
+            Fraction::from(1)
+        ])
+    );
+    let a: Matrix<i32> = Matrix::from([[1, 2], [2, 4]]);
+    assert_eq!(a.inverse(), Err(MatrixError::Singular));
+    assert_eq!(a.solve(&[1, 2]), Err(MatrixError::Singular));
+}
+
+#[test]
+fn test_exact_rank() {
+    let a: Matrix<i64> = Matrix::from([[1, 2, 3, 4], [2, 4, 6, 8], [0, 1, 0, 1]]);
+    assert_eq!(a.rank(), Ok(2));
+    assert_eq!(Matrix::<u8>::zeros(3, 2).rank(), Ok(0));
+    assert_eq!(Matrix::<u8>::identity(3).rank(), Ok(3));
+}
+
+#[test]
+fn test_exact_overflow() {
+    let a: Matrix<i64> = Matrix::from([[i64::MAX, 1, 1], [1, i64::MAX, 1], [1, 1, i64::MAX]]);
+    assert_eq!(a.determinant(), Err(MatrixError::Overflow));
+}
+
+#[test]
+fn test_fraction() {
+    assert_eq!(Fraction::new(4, -6), Fraction::new(-2, 3));
+    assert_eq!(Fraction::new(-2, 3).numer(), -2);
+    assert_eq!(Fraction::new(-2, 3).denom(), 3);
+    assert_eq!(Fraction::new(0, -5), Fraction::from(0));
+    assert_eq!(Fraction::new(6, 3).to_string(), "2");
+    assert_eq!(Fraction::new(1, 4).to_f64(), 0.25);
+}
+
+#[test]
+fn test_float_rank_matches_exact() {
+    // Products of random n x r and r x n integer matrices have rank at most
+    // r. Small integers are exact in `f64`, so the float path should agree
+    // with the exact one.
+    let mut state = 0x2545f4914f6cdd1d;
+    let mut next = || (crate::xorshift(&mut state) % 15) as i64 - 7;
+    for trial in 0..2000 {
+        let n = 2 + trial % 7;
+        let r = 1 + trial % n;
+        let b: Vec<i64> = (0..n * r).map(|_| next()).collect();
+        let c: Vec<i64> = (0..r * n).map(|_| next()).collect();
+        let product: Vec<i64> = (0..n * n)
+            .map(|k| (0..r).map(|m| b[k / n * r + m] * c[m * n + k % n]).sum())
+            .collect();
+        let exact = Matrix::from_vec(n, n, product.clone()).unwrap();
+        let float = Matrix::from_vec(n, n, product.iter().map(|&x| x as f64).collect()).unwrap();
+        assert_eq!(float.rank(), exact.rank().unwrap(), "{exact:?}");
+        assert_eq!(
+            float.inverse().is_ok(),
+            exact.determinant() != Ok(0),
+            "{exact:?}"
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use std::io::{self, BufRead, Read, Write};
+use thiserror::Error;
+
+use crate::Matrix;
+
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("invalid PNM header: {0}")]
+    Header(String),
+    #[error("unsupported maximum value {0}, only 8-bit images are supported")]
+    UnsupportedDepth(u32),
+    #[error("pixel data ends after {found} of {expected} bytes")]
+    Truncated { expected: usize, found: usize },
+}
+
+/// A clockwise rotation by a multiple of 90 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl Rotation {
+    /// `90`, `180` or `270`.
+    pub fn from_degrees(degrees: u32) -> Option<Self> {
+        match degrees {
+            90 => Some(Self::Quarter),
+            180 => Some(Self::Half),
+            270 => Some(Self::ThreeQuarters),
+            _ => None,
+        }
+    }
+}
+
+/// A mirror axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flip {
+    /// Swap left and right.
+    Horizontal,
+    /// Swap top and bottom.
+    Vertical,
+}
+
+impl<T> Matrix<T> {
+    pub fn flip(&mut self, flip: Flip) {
+        let (rows, cols) = self.dims();
+        if rows == 0 || cols == 0 {
+            return;
+        }
+        match flip {
+            Flip::Horizontal => {
+                for row in self.as_mut_slice().chunks_mut(cols) {
+                    row.reverse();
+                }
+            }
+            Flip::Vertical => {
+                let (top, bottom) = self.as_mut_slice().split_at_mut(rows / 2 * cols);
+                // Skip the middle row of an odd number of rows.
+                let bottom = &mut bottom[(rows % 2) * cols..];
+                for (upper, lower) in top.chunks_mut(cols).zip(bottom.chunks_mut(cols).rev()) {
+                    upper.swap_with_slice(lower);
+                }
+            }
+        }
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    /// Rotate clockwise. Quarter turns are a blocked transpose followed by a
+    /// flip; a half turn is both flips.
+    pub fn rotate(&self, rotation: Rotation) -> Self {
+        let mut rotated = match rotation {
+            Rotation::Quarter | Rotation::ThreeQuarters => self.transpose(),
+            Rotation::Half => self.clone(),
+        };
+        match rotation {
+            Rotation::Quarter => rotated.flip(Flip::Horizontal),
+            Rotation::Half => {
+                rotated.flip(Flip::Horizontal);
+                rotated.flip(Flip::Vertical);
+            }
+            Rotation::ThreeQuarters => rotated.flip(Flip::Vertical),
+        }
+        rotated
+    }
+}
+
+/// An 8-bit image as a matrix of pixels, with one row per line of the image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Image {
+    Gray(Matrix<u8>),
+    Rgb(Matrix<[u8; 3]>),
+}
+
+/// The longest header token accepted. The magic number, width, height and
+/// maximum value all need only a few characters.
+const MAX_TOKEN_LEN: usize = 20;
+
+/// Read the next whitespace-separated header token, skipping `#` comments,
+/// and consume the single whitespace byte after it.
+fn header_token(input: &mut impl BufRead) -> Result<String, ImageError> {
+    let mut token = String::new();
+    let mut comment = false;
+    loop {
+        let mut byte = [0];
+        if input.read(&mut byte)? == 0 {
+            return Err(ImageError::Header("unexpected end of file".to_string()));
+        }
+        let c = byte[0] as char;
+        if comment {
+            comment = c != '\n';
+        } else if c == '#' && token.is_empty() {
+            comment = true;
+        } else if c.is_ascii_whitespace() {
+            if !token.is_empty() {
+                return Ok(token);
+            }
+        } else if token.len() < MAX_TOKEN_LEN {
+            token.push(c);
+        } else {
+            return Err(ImageError::Header(format!(
+                "token longer than {MAX_TOKEN_LEN} bytes"
+            )));
+        }
+    }
+}
+
+fn header_number(input: &mut impl BufRead, name: &str) -> Result<u32, ImageError> {
+    let token = header_token(input)?;
+    token
+        .parse()
+        .map_err(|_| ImageError::Header(format!("invalid {name} {token:?}")))
+}
+
+impl Image {
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Gray(pixels) => pixels.cols(),
+            Self::Rgb(pixels) => pixels.cols(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            Self::Gray(pixels) => pixels.rows(),
+            Self::Rgb(pixels) => pixels.rows(),
+        }
+    }
+
+    pub fn rotate(&self, rotation: Rotation) -> Self {
+        match self {
+            Self::Gray(pixels) => Self::Gray(pixels.rotate(rotation)),
+            Self::Rgb(pixels) => Self::Rgb(pixels.rotate(rotation)),
+        }
+    }
+
+    pub fn flip(&mut self, flip: Flip) {
+        match self {
+            Self::Gray(pixels) => pixels.flip(flip),
+            Self::Rgb(pixels) => pixels.flip(flip),
+        }
+    }
+
+    /// Read a binary PGM (`P5`) or PPM (`P6`) image with a maximum value of
+    /// at most 255.
+    pub fn read_pnm(mut input: impl BufRead) -> Result<Self, ImageError> {
+        let magic = header_token(&mut input)?;
+        if magic != "P5" && magic != "P6" {
+            return Err(ImageError::Header(format!(
+                "expected P5 or P6, found {magic:?}"
+            )));
+        }
+        let width = header_number(&mut input, "width")? as usize;
+        let height = header_number(&mut input, "height")? as usize;
+        let max = header_number(&mut input, "maximum value")?;
+        if max == 0 || max > 255 {
+            return Err(ImageError::UnsupportedDepth(max));
+        }
+        let channels = if magic == "P5" { 1 } else { 3 };
+        let expected = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(channels))
+            .ok_or_else(|| {
+                ImageError::Header(format!("image size {width}x{height} is too large"))
+            })?;
+        // The header may lie about the size, so only allocate for the pixel
+        // data that is actually there.
+        let mut data = Vec::new();
+        input.take(expected as u64).read_to_end(&mut data)?;
+        if data.len() < expected {
+            return Err(ImageError::Truncated {
+                expected,
+                found: data.len(),
+            });
+        }
+        let image = if channels == 1 {
+            Self::Gray(Matrix::from_vec(height, width, data).unwrap())
+        } else {
+            let pixels = data.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect();
+            Self::Rgb(Matrix::from_vec(height, width, pixels).unwrap())
+        };
+        Ok(image)
+    }
+
+    /// Write as a binary PGM or PPM image.
+    pub fn write_pnm(&self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            Self::Gray(pixels) => {
+                writeln!(out, "P5\n{} {}\n255", pixels.cols(), pixels.rows())?;
+                out.write_all(pixels.as_slice())
+            }
+            Self::Rgb(pixels) => {
+                writeln!(out, "P6\n{} {}\n255", pixels.cols(), pixels.rows())?;
+                out.write_all(pixels.as_slice().as_flattened())
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rotate() {
+    let m = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(
+        m.rotate(Rotation::Quarter),
+        Matrix::from([[4, 1], [5, 2], [6, 3]])
+    );
+    assert_eq!(
+        m.rotate(Rotation::Half),
+        Matrix::from([[6, 5, 4], [3, 2, 1]])
+    );
+    assert_eq!(
+        m.rotate(Rotation::ThreeQuarters),
+        Matrix::from([[3, 6], [2, 5], [1, 4]])
+    );
+    // Four quarter turns, or a quarter and three quarters, change nothing.
+    let big = Matrix::from_vec(37, 70, (0..37 * 70).collect::<Vec<u32>>()).unwrap();
+    let mut turned = big.clone();
+    for _ in 0..4 {
+        turned = turned.rotate(Rotation::Quarter);
+    }
+    assert_eq!(turned, big);
+    assert_eq!(
+        big.rotate(Rotation::Quarter)
+            .rotate(Rotation::ThreeQuarters),
+        big
+    );
+    assert_eq!(
+        big.rotate(Rotation::Quarter).rotate(Rotation::Quarter),
+        big.rotate(Rotation::Half)
+    );
+}
+
+#[test]
+fn test_flip() {
+    let mut m = Matrix::from([[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+    m.flip(Flip::Horizontal);
+    assert_eq!(m, Matrix::from([[3, 2, 1], [6, 5, 4], [9, 8, 7]]));
+    m.flip(Flip::Vertical);
+    assert_eq!(m, Matrix::from([[9, 8, 7], [6, 5, 4], [3, 2, 1]]));
+    let mut m = Matrix::from([[1, 2], [3, 4]]);
+    m.flip(Flip::Vertical);
+    assert_eq!(m, Matrix::from([[3, 4], [1, 2]]));
+    // A transpose is a quarter turn and a flip.
+    let mut m = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+    let transposed = m.transpose();
+    m.flip(Flip::Vertical);
+    assert_eq!(m.rotate(Rotation::Quarter), transposed);
+    let mut empty = Matrix::<u8>::zeros(0, 3);
+    empty.flip(Flip::Vertical);
+    assert_eq!(empty.dims(), (0, 3));
+}
+
+#[test]
+fn test_pnm_round_trip() {
+    let gray = Image::Gray(Matrix::from([[0, 64, 128], [192, 255, 1]]));
+    let mut out = Vec::new();
+    gray.write_pnm(&mut out).unwrap();
+    assert!(out.starts_with(b"P5\n3 2\n255\n"));
+    assert_eq!(out.len(), 11 + 6);
+    assert_eq!(Image::read_pnm(&out[..]).unwrap(), gray);
+
+    let rgb = Image::Rgb(Matrix::from([[[255, 0, 0], [0, 255, 0]]]));
+    let mut out = Vec::new();
+    rgb.write_pnm(&mut out).unwrap();
+    assert_eq!(out, b"P6\n2 1\n255\n\xff\x00\x00\x00\xff\x00");
+    let read = Image::read_pnm(&out[..]).unwrap();
+    assert_eq!((read.width(), read.height()), (2, 1));
+    assert_eq!(read, rgb);
+}
+
+#[test]
+fn test_pnm_header_comments() {
+    let data = b"P5 # gray\n# made by hand\n2  1\n255\n\x0a\x20";
+    assert_eq!(
+        Image::read_pnm(&data[..]).unwrap(),
+        // The raster may start with bytes that look like whitespace.
+        Image::Gray(Matrix::from([[b'\n', b' ']]))
+    );
+}
+
+#[test]
+fn test_pnm_errors() {
+    assert!(matches!(
+        Image::read_pnm(&b"P3\n1 1\n255\n1 2 3\n"[..]),
+        Err(ImageError::Header(_))
+    ));
+    assert!(matches!(
+        Image::read_pnm(&b"P5\n1 x\n255\n"[..]),
+        Err(ImageError::Header(_))
+    ));
+    assert!(matches!(
+        Image::read_pnm(&b"P5\n1 1\n65535\n\0\0"[..]),
+        Err(ImageError::UnsupportedDepth(65535))
+    ));
+    assert!(matches!(
+        Image::read_pnm(&b"P6\n2 2\n255\n\0\0\0"[..]),
+        Err(ImageError::Truncated {
+            expected: 12,
+            found: 3
+        })
+    ));
+    // A header token is not read into memory without bound.
+    let mut long = b"P5\n".to_vec();
+    long.resize(1 << 20, b'1');
+    assert!(matches!(
+        Image::read_pnm(&long[..]),
+        Err(ImageError::Header(reason)) if reason.starts_with("token longer")
+    ));
+    // Sizes from the header are neither trusted to fit in a `usize` nor
+    // allocated up front.
+    assert!(matches!(
+        Image::read_pnm(&b"P6\n4294967295 4294967295\n255\n"[..]),
+        Err(ImageError::Header(_))
+    ));
+    assert!(matches!(
+        Image::read_pnm(&b"P6\n60000 60000\n255\n\0\0\0"[..]),
+        Err(ImageError::Truncated {
+            expected: 10_800_000_000,
+            found: 3
+        })
+    ));
+}
+
+#[test]
+fn test_image_rotate_and_flip() {
+    let mut image = Image::Rgb(Matrix::from([[[1, 1, 1], [2, 2, 2], [3, 3, 3]]]));
+    let rotated = image.rotate(Rotation::Quarter);
+    assert_eq!((rotated.width(), rotated.height()), (1, 3));
+    image.flip(Flip::Horizontal);
+    assert_eq!(
+        image,
+        Image::Rgb(Matrix::from([[[3, 3, 3], [2, 2, 2], [1, 1, 1]]]))
+    );
+}
@@ -0,0 +1,71 @@
+use nested_arrays::{Flip, Image, Rotation};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: imgrot INPUT OUTPUT [--rotate 90|180|270 | --flip h|v]...
+
+Reads a binary PGM or PPM image, applies the operations in order and writes
+the result in the same format.";
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Rotate(Rotation),
+    Flip(Flip),
+}
+
+fn parse_operations(args: &[String]) -> Option<Vec<Operation>> {
+    let mut operations = Vec::new();
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        let operation = match (arg, args.next()?) {
+            ("--rotate", degrees) => {
+                Operation::Rotate(Rotation::from_degrees(degrees.parse().ok()?)?)
+            }
+            ("--flip", "h") => Operation::Flip(Flip::Horizontal),
+            ("--flip", "v") => Operation::Flip(Flip::Vertical),
+            _ => return None,
+        };
+        operations.push(operation);
+    }
+    Some(operations)
+}
+
+fn run(
+    input: &str,
+    output: &str,
+    operations: &[Operation],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut image = Image::read_pnm(BufReader::new(File::open(input)?))?;
+    for operation in operations {
+        match *operation {
+            Operation::Rotate(rotation) => image = image.rotate(rotation),
+            Operation::Flip(flip) => image.flip(flip),
+        }
+    }
+    let mut out = BufWriter::new(File::create(output)?);
+    image.write_pnm(&mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Usage errors exit with 2, other failures with 1.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (Some(input), Some(output)) = (args.first(), args.get(1)) else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let Some(operations) = parse_operations(&args[2..]) else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    match run(input, output, &operations) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("imgrot: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::{CsrMatrix, Matrix, Scalar};
+
+#[derive(Error, Debug)]
+pub enum ReadError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("line {line}, column {column}: {reason}")]
+    Parse {
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+}
+
+/// The header line of a Matrix Market file in coordinate format.
+const MARKET_BANNER: &str = "%%MatrixMarket";
+
+fn parse_error(line: usize, column: usize, reason: impl Into<String>) -> ReadError {
+    ReadError::Parse {
+        line,
+        column,
+        reason: reason.into(),
+    }
+}
+
+/// Parse `cell`, which starts at `column` of `line`.
+fn parse_cell<T: FromStr>(cell: &str, line: usize, column: usize) -> Result<T, ReadError> {
+    cell.parse().map_err(|_| {
+        let reason = if cell.is_empty() {
+            "empty cell".to_string()
+        } else {
+            format!("invalid number {cell:?}")
+        };
+        parse_error(line, column, reason)
+    })
+}
+
+/// The whitespace-separated tokens of `line` with their 1-based columns.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push((s + 1, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+impl<T: Scalar + FromStr> Matrix<T> {
+    /// Read comma-separated rows, one per line. Whitespace around the cells
+    /// and blank lines are ignored, and every row must have as many cells as
+    /// the first.
+    pub fn read_csv(input: impl BufRead) -> Result<Self, ReadError> {
+        let mut data = Vec::new();
+        let mut rows = 0;
+        let mut cols = 0;
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut column = 1;
+            let mut cells = 0;
+            for cell in line.split(',') {
+                let padding = cell.len() - cell.trim_start().len();
+                data.push(parse_cell(cell.trim(), i + 1, column + padding)?);
+                column += cell.len() + 1;
+                cells += 1;
+            }
+            if rows == 0 {
+                cols = cells;
+            } else if cells != cols {
+                return Err(parse_error(
+                    i + 1,
+                    column - 1,
+                    format!("expected {cols} cells, found {cells}"),
+                ));
+            }
+            rows += 1;
+        }
+        Ok(Self::from_vec(rows, cols, data).expect("every row has `cols` elements"))
+    }
+
+    /// Read a Matrix Market file in coordinate format. See
+    /// `CsrMatrix::read_matrix_market`.
+    pub fn read_matrix_market(input: impl BufRead) -> Result<Self, ReadError> {
+        // A size can be small enough to address and still too large to
+        // allocate, so reserve the storage while checking the size line.
+        let mut data = Vec::new();
+        let reserve = |rows: usize, cols| {
+            rows.checked_mul(cols)
+                .is_some_and(|len| data.try_reserve_exact(len).is_ok())
+        };
+        let (rows, cols, triplets) = read_market_entries(input, reserve)?;
+        data.resize(rows * cols, T::ZERO);
+        let mut dense = Self::from_vec(rows, cols, data).expect("the size was checked");
+        for (row, col, value) in triplets {
+            dense[(row, col)] = dense[(row, col)] + value;
+        }
+        Ok(dense)
+    }
+}
+
+impl<T: Scalar + Display> Matrix<T> {
+    /// Write the rows as comma-separated lines.
+    pub fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        for row in self.iter_rows() {
+            for (j, x) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(out, ",")?;
+                }
+                write!(out, "{x}")?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Write the non-zero elements in Matrix Market coordinate format.
+    pub fn write_matrix_market(&self, out: &mut impl Write) -> io::Result<()> {
+        CsrMatrix::from(self).write_matrix_market(out)
+    }
+}
+
+/// `(row, col, value)` entries of a sparse matrix.
+type Triplets<T> = Vec<(usize, usize, T)>;
+
+/// Whether `count` values of type `T` fit in one allocation.
+fn fits_in_memory<T>(count: Option<usize>) -> bool {
+    count
+        .and_then(|count| count.checked_mul(std::mem::size_of::<T>()))
+        .is_some_and(|bytes| bytes <= isize::MAX as usize)
+}
+
+/// The size and the entries of a Matrix Market file, with the indices
+/// checked against the size. `fits` decides whether a matrix of the size in
+/// the header can be stored at all, before any entries are read, and may
+/// allocate for it.
+fn read_market_entries<T: Scalar + FromStr>(
+    input: impl BufRead,
+    mut fits: impl FnMut(usize, usize) -> bool,
+) -> Result<(usize, usize, Triplets<T>), ReadError> {
+    let mut lines = input.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let (_, banner) = lines
+        .next()
+        .ok_or_else(|| parse_error(1, 1, "missing Matrix Market banner"))?;
+    let banner = banner?;
+    let header = tokens(&banner);
+    let words: Vec<String> = header.iter().map(|(_, t)| t.to_lowercase()).collect();
+    if words.first().map(String::as_str) != Some(&MARKET_BANNER.to_lowercase()) {
+        return Err(parse_error(1, 1, "missing Matrix Market banner"));
+    }
+    if words.len() != 5 {
+        return Err(parse_error(
+            1,
+            1,
+            "expected `matrix coordinate <field> <symmetry>`",
+        ));
+    }
+    let unsupported =
+        |k: usize| parse_error(1, header[k].0, format!("unsupported {:?}", header[k].1));
+    if words[1] != "matrix" {
+        return Err(unsupported(1));
+    }
+    if words[2] != "coordinate" {
+        return Err(unsupported(2));
+    }
+    let pattern = match words[3].as_str() {
+        "real" | "integer" => false,
+        "pattern" => true,
+        _ => return Err(unsupported(3)),
+    };
+    let symmetric = match words[4].as_str() {
+        "general" => false,
+        "symmetric" => true,
+        _ => return Err(unsupported(4)),
+    };
+
+    let mut size = None;
+    let mut triplets = Vec::new();
+    let mut found = 0;
+    let mut last_line = 1;
+    for (n, line) in lines {
+        let line = line?;
+        last_line = n;
+        if line.starts_with('%') || line.trim().is_empty() {
+            continue;
+        }
+        let fields = tokens(&line);
+        let Some((rows, cols, entries)) = size else {
+            let [(c1, rows), (c2, cols), (c3, entries)] = fields[..] else {
+                return Err(parse_error(n, 1, "expected `rows cols entries`"));
+            };
+            let rows = parse_cell::<usize>(rows, n, c1)?;
+            let cols = parse_cell::<usize>(cols, n, c2)?;
+            if !fits(rows, cols) {
+                return Err(parse_error(
+                    n,
+                    c1,
+                    format!("a {rows}x{cols} matrix does not fit in memory"),
+                ));
+            }
+            size = Some((rows, cols, parse_cell::<usize>(entries, n, c3)?));
+            continue;
+        };
+        let expected = if pattern { 2 } else { 3 };
+        if fields.len() != expected {
+            return Err(parse_error(
+                n,
+                1,
+                format!("expected {expected} fields, found {}", fields.len()),
+            ));
+        }
+        if found == entries {
+            return Err(parse_error(
+                n,
+                1,
+                format!("more than the {entries} entries in the size line"),
+            ));
+        }
+        let index = |(column, field): (usize, &str), bound: usize| {
+            let index: usize = parse_cell(field, n, column)?;
+            if !(1..=bound).contains(&index) {
+                return Err(parse_error(
+                    n,
+                    column,
+                    format!("index {index} is not within 1..={bound}"),
+                ));
+            }
+            Ok(index - 1)
+        };
+        let row = index(fields[0], rows)?;
+        let col = index(fields[1], cols)?;
+        let value = if pattern {
+            T::ONE
+        } else {
+            parse_cell(fields[2].1, n, fields[2].0)?
+        };
+        triplets.push((row, col, value));
+        found += 1;
+        if symmetric && row != col {
+            triplets.push((col, row, value));
+        }
+    }
+    let Some((rows, cols, entries)) = size else {
+        return Err(parse_error(last_line + 1, 1, "missing size line"));
+    };
+    if found != entries {
+        return Err(parse_error(
+            last_line + 1,
+            1,
+            format!("expected {entries} entries, found {found}"),
+        ));
+    }
+    Ok((rows, cols, triplets))
+}
+
+impl<T: Scalar + FromStr> CsrMatrix<T> {
+    /// Read a Matrix Market file in coordinate format: a
+    /// `%%MatrixMarket matrix coordinate <field> <symmetry>` banner, `%`
+    /// comment lines, a `rows cols entries` size line and one
+    /// `row col value` line per entry, with 1-based indices.
+    ///
+    /// The `real` and `integer` fields are read with `T::from_str`, and
+    /// `pattern` entries, which have no value, are read as ones. For a
+    /// `symmetric` matrix only the lower triangle is stored in the file.
+    pub fn read_matrix_market(input: impl BufRead) -> Result<Self, ReadError> {
+        // One row pointer per row, plus one past the end.
+        let fits = |rows: usize, _| fits_in_memory::<usize>(rows.checked_add(1));
+        let (rows, cols, triplets) = read_market_entries(input, fits)?;
+        Ok(Self::from_triplets(rows, cols, &triplets).expect("indices were checked"))
+    }
+}
+
+impl<T: Display> CsrMatrix<T> {
+    /// Write in Matrix Market coordinate format as a `real general` matrix,
+    /// which readers also accept for integer values.
+    pub fn write_matrix_market(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{MARKET_BANNER} matrix coordinate real general")?;
+        writeln!(out, "{} {} {}", self.rows(), self.cols(), self.nnz())?;
+        for row in 0..self.rows() {
+            for (col, value) in self.row(row) {
+                writeln!(out, "{} {} {value}", row + 1, col + 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn assert_parse_error<T: std::fmt::Debug>(
+    result: Result<T, ReadError>,
+    line: usize,
+    column: usize,
+    reason: &str,
+) {
+    match result {
+        Err(ReadError::Parse {
+            line: l,
+            column: c,
+            reason: r,
+        }) => assert_eq!((l, c, r.as_str()), (line, column, reason)),
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_csv_round_trip() {
+    let m = Matrix::from([[1.5, -2.0, 0.0], [4.0, 5.25, 6.0]]);
+    let mut out = Vec::new();
+    m.write_csv(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out.clone()).unwrap(),
+        "1.5,-2,0\n4,5.25,6\n"
+    );
+    assert_eq!(Matrix::read_csv(&out[..]).unwrap(), m);
+
+    let m: Matrix<i32> = Matrix::read_csv(" 1, 2 \n\n3 ,4\n".as_bytes()).unwrap();
+    assert_eq!(m, Matrix::from([[1, 2], [3, 4]]));
+    let m: Matrix<i32> = Matrix::read_csv("".as_bytes()).unwrap();
+    assert_eq!(m.dims(), (0, 0));
+}
+
+#[test]
+fn test_csv_errors() {
+    assert_parse_error(
+        Matrix::<i32>::read_csv("1,2,3\n4,5\n".as_bytes()),
+        2,
+        4,
+        "expected 3 cells, found 2",
+    );
+    assert_parse_error(
+        Matrix::<i32>::read_csv("1,2\n3, x4\n".as_bytes()),
+        2,
+        4,
+        "invalid number \"x4\"",
+    );
+    assert_parse_error(
+        Matrix::<f64>::read_csv("1,,3\n".as_bytes()),
+        1,
+        3,
+        "empty cell",
+    );
+    let err = Matrix::<f64>::read_csv("1,a\n".as_bytes()).unwrap_err();
+    assert_eq!(err.to_string(), "line 1, column 3: invalid number \"a\"");
+}
+
+#[test]
+fn test_matrix_market_round_trip() {
+    let m = Matrix::from([[0, 3, 0], [4, 0, 0], [0, 0, -5], [0, 0, 0]]);
+    let mut out = Vec::new();
+    m.write_matrix_market(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out.clone()).unwrap(),
+        "%%MatrixMarket matrix coordinate real general\n4 3 3\n1 2 3\n2 1 4\n3 3 -5\n"
+    );
+    assert_eq!(Matrix::read_matrix_market(&out[..]).unwrap(), m);
+    let sparse = CsrMatrix::read_matrix_market(&out[..]).unwrap();
+    assert_eq!(sparse, CsrMatrix::from(&m));
+}
+
+#[test]
+fn test_matrix_market_variants() {
+    let text = "%%MatrixMarket matrix coordinate integer symmetric\n\
+                % a comment\n\
+                3 3 3\n\
+                1 1 2\n\
+                3 1 7\n\
+                2 2 1\n";
+    let m: Matrix<i64> = Matrix::read_matrix_market(text.as_bytes()).unwrap();
+    assert_eq!(m, Matrix::from([[2, 0, 7], [0, 1, 0], [7, 0, 0]]));
+
+    let text = "%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n2 1\n";
+    let m: Matrix<f64> = Matrix::read_matrix_market(text.as_bytes()).unwrap();
+    assert_eq!(m, Matrix::from([[0.0, 1.0], [1.0, 0.0]]));
+}
+
+#[test]
+fn test_matrix_market_errors() {
+    let read = |text: &str| Matrix::<f64>::read_matrix_market(text.as_bytes());
+    assert_parse_error(read(""), 1, 1, "missing Matrix Market banner");
+    assert_parse_error(read("1 2 3\n"), 1, 1, "missing Matrix Market banner");
+    assert_parse_error(
+        read("%%MatrixMarket matrix array real general\n"),
+        1,
+        23,
+        "unsupported \"array\"",
+    );
+    let header = "%%MatrixMarket matrix coordinate real general\n";
+    assert_parse_error(read(header), 2, 1, "missing size line");
+    assert_parse_error(
+        read(&format!("{header}2 2 1\n1 3 1.0\n")),
+        3,
+        3,
+        "index 3 is not within 1..=2",
+    );
+    assert_parse_error(
+        read(&format!("{header}2 2 1\n1 1 one\n")),
+        3,
+        5,
+        "invalid number \"one\"",
+    );
+    assert_parse_error(
+        read(&format!("{header}2 2 2\n1 1 1.0\n")),
+        4,
+        1,
+        "expected 2 entries, found 1",
+    );
+    assert_parse_error(
+        read(&format!("{header}2 2 1\n1 1\n")),
+        3,
+        1,
+        "expected 3 fields, found 2",
+    );
+    // Sizes that cannot be allocated are rejected before trying.
+    assert_parse_error(
+        CsrMatrix::<f64>::read_matrix_market(
+            format!("{header}18446744073709551615 1 0\n").as_bytes(),
+        ),
+        2,
+        1,
+        "a 18446744073709551615x1 matrix does not fit in memory",
+    );
+    assert_parse_error(
+        read(&format!("{header}3000000000 3000000000 0\n")),
+        2,
+        1,
+        "a 3000000000x3000000000 matrix does not fit in memory",
+    );
+    // The product fits in a `usize`, but the allocation does not succeed.
+    assert_parse_error(
+        read(&format!("{header}1000000000 1000000000 0\n")),
+        2,
+        1,
+        "a 1000000000x1000000000 matrix does not fit in memory",
+    );
+    assert_parse_error(
+        read(&format!("{header}% comment\n 4294967296 4294967296 0\n")),
+        3,
+        2,
+        "a 4294967296x4294967296 matrix does not fit in memory",
+    );
+    let sparse =
+        CsrMatrix::<f64>::read_matrix_market(format!("{header}1 3000000000 0\n").as_bytes());
+    assert_eq!(sparse.unwrap().dims(), (1, 3000000000));
+}
@@ -0,0 +1,95 @@
+mod elimination;
+mod image;
+mod io;
+mod matrix;
+mod sparse;
+mod transpose;
+
+pub use elimination::{Fraction, Lu};
+pub use image::{Flip, Image, ImageError, Rotation};
+pub use io::ReadError;
+pub use matrix::{Matrix, MatrixError, Scalar};
+pub use sparse::{CscMatrix, CsrMatrix};
+pub use transpose::{transpose_blocked, transpose_naive, transpose_slice_in_place, BLOCK_SIZE};
+
+/// Transpose a matrix of any shape, turning its rows into columns.
+pub fn transpose<T: Copy, const R: usize, const C: usize>(matrix: [[T; C]; R]) -> [[T; R]; C] {
+    std::array::from_fn(|i| std::array::from_fn(|j| matrix[j][i]))
+}
+
+/// Transpose a square matrix in place, by swapping the elements above the
+/// diagonal with those below it.
+pub fn transpose_in_place<T, const N: usize>(matrix: &mut [[T; N]; N]) {
+    for i in 0..N {
+        // Split so that row i and row j can be borrowed mutably at once.
+        let (upper, lower) = matrix.split_at_mut(i + 1);
+        for (j, row) in lower.iter_mut().enumerate() {
+            std::mem::swap(&mut upper[i][i + 1 + j], &mut row[i]);
+        }
+    }
+}
+
+/// Xorshift64, for random test data without a `rand` dependency.
+#[cfg(test)]
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn test_transpose() {
+    let actual = [
+        [1, 2, 3], // <-- the comment makes rustfmt add a newline
+        [4, 5, 6],
+        [7, 8, 9],
+    ];
+    let expected = [
+        [1, 4, 7], // <-- the comment makes rustfmt add a newline
+        [2, 5, 8],
+        [3, 6, 9],
+    ];
+
+    assert_eq!(transpose(actual), expected);
+}
+
+#[test]
+fn test_transpose_non_square() {
+    let actual = [
+        [1.0, 2.0, 3.0, 4.0], // <-- the comment makes rustfmt add a newline
+        [5.0, 6.0, 7.0, 8.0],
+    ];
+    let expected = [
+        [1.0, 5.0], // <-- the comment makes rustfmt add a newline
+        [2.0, 6.0],
+        [3.0, 7.0],
+        [4.0, 8.0],
+    ];
+
+    assert_eq!(transpose(actual), expected);
+    assert_eq!(transpose(expected), actual);
+    assert_eq!(transpose([['a', 'b', 'c']]), [['a'], ['b'], ['c']]);
+    assert_eq!(transpose::<u8, 0, 3>([]), [[], [], []]);
+}
+
+#[test]
+fn test_transpose_in_place() {
+    let mut matrix = [
+        [1, 2, 3, 4], // <-- the comment makes rustfmt add a newline
+        [5, 6, 7, 8],
+        [9, 10, 11, 12],
+        [13, 14, 15, 16],
+    ];
+    let expected = transpose(matrix);
+    transpose_in_place(&mut matrix);
+    assert_eq!(matrix, expected);
+
+    // The elements do not need to be `Copy`.
+    let mut names = [
+        [String::from("a"), String::from("b")],
+        [String::from("c"), String::from("d")],
+    ];
+    transpose_in_place(&mut names);
+    assert_eq!(names, [["a", "c"], ["b", "d"]]);
+}
@@ -0,0 +1,455 @@
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+use thiserror::Error;
+
+use crate::transpose;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MatrixError {
+    #[error("cannot {op} a {}x{} and a {}x{} matrix", .left.0, .left.1, .right.0, .right.1)]
+    DimensionMismatch {
+        op: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    #[error("row {row} has {found} elements, expected {expected}")]
+    RaggedRows {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("{len} elements do not fill a {rows}x{cols} matrix")]
+    WrongLength {
+        rows: usize,
+        cols: usize,
+        len: usize,
+    },
+    #[error("entry ({row}, {col}) is out of bounds for a {rows}x{cols} matrix")]
+    OutOfBounds {
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    },
+    #[error("expected a square matrix, found a {rows}x{cols} matrix")]
+    NotSquare { rows: usize, cols: usize },
+    #[error("the matrix is singular")]
+    Singular,
+    #[error("an intermediate value overflowed")]
+    Overflow,
+}
+
+/// A number type that can be stored in a `Matrix`.
+pub trait Scalar:
+    Copy + PartialEq + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_scalar {
+    ($zero:literal, $one:literal: $($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+            }
+        )*
+    };
+}
+
+impl_scalar!(0, 1: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_scalar!(0.0, 1.0: f32, f64);
+
+/// A dense, heap-allocated matrix stored in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Create a matrix from its elements in row-major order.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Self, MatrixError> {
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(MatrixError::WrongLength {
+                rows,
+                cols,
+                len: data.len(),
+            });
+        }
+        Ok(Self { rows, cols, data })
+    }
+
+    /// Create a matrix from a list of rows of equal length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, MatrixError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        let row_count = rows.len();
+        let mut data = Vec::with_capacity(row_count * cols);
+        for (row, elements) in rows.into_iter().enumerate() {
+            if elements.len() != cols {
+                return Err(MatrixError::RaggedRows {
+                    row,
+                    expected: cols,
+                    found: elements.len(),
+                });
+            }
+            data.extend(elements);
+        }
+        Ok(Self {
+            rows: row_count,
+            cols,
+            data,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The shape as `(rows, cols)`.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    /// The elements in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// One slice per row, which is empty if the matrix has no columns.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(move |row| self.row(row))
+    }
+
+    /// Transpose without allocating, following the cycles of the index
+    /// permutation. Slower than `transpose`, but needs only one bit of
+    /// scratch space per element.
+    pub fn transpose_in_place(&mut self) {
+        transpose::transpose_slice_in_place(&mut self.data, self.rows, self.cols);
+        (self.rows, self.cols) = (self.cols, self.rows);
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    /// Transpose into a new matrix, using cache-friendly tiles.
+    pub fn transpose(&self) -> Self {
+        let mut data = self.data.clone();
+        transpose::transpose_blocked(&self.data, self.rows, self.cols, &mut data);
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            data,
+        }
+    }
+}
+
+impl<T: Scalar> Matrix<T> {
+    /// # Panics
+    ///
+    /// Panics if `rows * cols` overflows a `usize`.
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        let len = rows
+            .checked_mul(cols)
+            .unwrap_or_else(|| panic!("a {rows}x{cols} matrix is too large"));
+        Self {
+            rows,
+            cols,
+            data: vec![T::ZERO; len],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut m = Self::zeros(size, size);
+        for i in 0..size {
+            m[(i, i)] = T::ONE;
+        }
+        m
+    }
+
+    /// Multiply every element by `factor`.
+    pub fn scale(&self, factor: T) -> Self {
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&x| x * factor).collect(),
+        }
+    }
+
+    fn elementwise(
+        &self,
+        other: &Self,
+        op: &'static str,
+        f: impl Fn(T, T) -> T,
+    ) -> Result<Self, MatrixError> {
+        if self.dims() != other.dims() {
+            return Err(MatrixError::DimensionMismatch {
+                op,
+                left: self.dims(),
+                right: other.dims(),
+            });
+        }
+        Ok(Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        })
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T> {
+    fn from(array: [[T; C]; R]) -> Self {
+        Self {
+            rows: R,
+            cols: C,
+            data: array.iter().flatten().copied().collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "index ({row}, {col}) out of bounds for a {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "index ({row}, {col}) out of bounds for a {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl<T: Scalar> Add for &Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn add(self, other: &Matrix<T>) -> Self::Output {
+        self.elementwise(other, "add", |a, b| a + b)
+    }
+}
+
+impl<T: Scalar> Sub for &Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    fn sub(self, other: &Matrix<T>) -> Self::Output {
+        self.elementwise(other, "subtract", |a, b| a - b)
+    }
+}
+
+impl<T: Scalar> Mul for &Matrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    /// The matrix product.
+    fn mul(self, other: &Matrix<T>) -> Self::Output {
+        if self.cols != other.rows {
+            return Err(MatrixError::DimensionMismatch {
+                op: "multiply",
+                left: self.dims(),
+                right: other.dims(),
+            });
+        }
+        let mut product = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            // The i-k-j loop order walks both operands row by row.
+            for (k, &a) in self.row(i).iter().enumerate() {
+                for (j, &b) in other.row(k).iter().enumerate() {
+                    product[(i, j)] = product[(i, j)] + a * b;
+                }
+            }
+        }
+        Ok(product)
+    }
+}
+
+impl<T: Scalar> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, factor: T) -> Matrix<T> {
+        self.scale(factor)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    /// Render one row per line with right-aligned columns. A precision like
+    /// `{:.2}` is applied to every element.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self
+            .data
+            .iter()
+            .map(|x| match f.precision() {
+                Some(precision) => format!("{x:.precision$}"),
+                None => x.to_string(),
+            })
+            .collect();
+        let widths: Vec<usize> = (0..self.cols)
+            .map(|j| {
+                (0..self.rows)
+                    .map(|i| cells[i * self.cols + j].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for i in 0..self.rows {
+            write!(f, "[")?;
+            for (j, width) in widths.iter().enumerate() {
+                if j > 0 {
+                    write!(f, "  ")?;
+                }
+                write!(f, "{:>width$}", cells[i * self.cols + j])?;
+            }
+            writeln!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_constructors() {
+    let m = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    assert_eq!(m.dims(), (2, 3));
+    assert_eq!(m, Matrix::from([[1, 2, 3], [4, 5, 6]]));
+    assert_eq!(m, Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]).unwrap());
+    assert_eq!(m[(1, 0)], 4);
+    assert_eq!(m.get(2, 0), None);
+    assert_eq!(
+        Matrix::from_rows(vec![vec![1, 2], vec![3]]),
+        Err(MatrixError::RaggedRows {
+            row: 1,
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(
+        Matrix::from_vec(2, 2, vec![1, 2, 3]),
+        Err(MatrixError::WrongLength {
+            rows: 2,
+            cols: 2,
+            len: 3
+        })
+    );
+    assert_eq!(Matrix::<i32>::zeros(2, 2), Matrix::from([[0, 0], [0, 0]]));
+    assert_eq!(
+        Matrix::<f64>::identity(2),
+        Matrix::from([[1.0, 0.0], [0.0, 1.0]])
+    );
+}
+
+#[test]
+fn test_arithmetic() {
+    let a = Matrix::from([[1, 2], [3, 4]]);
+    let b = Matrix::from([[5, 6], [7, 8]]);
+    assert_eq!(&a + &b, Ok(Matrix::from([[6, 8], [10, 12]])));
+    assert_eq!(&b - &a, Ok(Matrix::from([[4, 4], [4, 4]])));
+    assert_eq!(&a * &b, Ok(Matrix::from([[19, 22], [43, 50]])));
+    assert_eq!(&a * 3, Matrix::from([[3, 6], [9, 12]]));
+    assert_eq!(&a * &Matrix::identity(2), Ok(a.clone()));
+
+    let c = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(&a * &c, Ok(Matrix::from([[9, 12, 15], [19, 26, 33]])));
+    assert_eq!(
+        &c * &a,
+        Err(MatrixError::DimensionMismatch {
+            op: "multiply",
+            left: (2, 3),
+            right: (2, 2)
+        })
+    );
+    assert!(matches!(
+        &a + &c,
+        Err(MatrixError::DimensionMismatch { op: "add", .. })
+    ));
+}
+
+#[test]
+fn test_transpose_matches_arrays() {
+    let array = [[1, 2, 3, 4], [5, 6, 7, 8]];
+    assert_eq!(
+        Matrix::from(array).transpose(),
+        Matrix::from(crate::transpose(array))
+    );
+}
+
+#[test]
+fn test_transpose_in_place() {
+    let mut m = Matrix::from([[1, 2, 3, 4], [5, 6, 7, 8]]);
+    let expected = m.transpose();
+    m.transpose_in_place();
+    assert_eq!(m, expected);
+    assert_eq!(m.dims(), (4, 2));
+}
+
+#[test]
+fn test_display() {
+    let m = Matrix::from([[1, -20, 3], [400, 5, 6]]);
+    assert_eq!(m.to_string(), "[  1  -20  3]\n[400    5  6]\n");
+    let m = Matrix::from([[1.0, 0.5], [0.25, 2.0]]);
+    assert_eq!(format!("{m:.2}"), "[1.00  0.50]\n[0.25  2.00]\n");
+}
+
+#[test]
+fn test_iter_rows() {
+    let m = Matrix::from([[1, 2], [3, 4], [5, 6]]);
+    let rows: Vec<&[i32]> = m.iter_rows().collect();
+    assert_eq!(rows, [[1, 2], [3, 4], [5, 6]]);
+    // A matrix without columns still has one (empty) row per row.
+    let empty = Matrix::<i32>::zeros(3, 0);
+    assert_eq!(empty.iter_rows().count(), 3);
+    assert!(empty.iter_rows().all(<[i32]>::is_empty));
+    assert_eq!(Matrix::<i32>::zeros(0, 3).iter_rows().count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "too large")]
+fn test_zeros_overflow() {
+    let _ = Matrix::<u8>::zeros(usize::MAX, 2);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_index_out_of_bounds() {
+    let m = Matrix::from([[1, 2], [3, 4]]);
+    let _ = m[(0, 2)];
+}
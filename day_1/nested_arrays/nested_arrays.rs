@@ -1,14 +1,39 @@
-fn transpose(matrix: [[i32; 3]; 3]) -> [[i32; 3]; 3] {
-    let mut transposed = matrix.clone();
-    for i in 0..matrix.len() {
-        for j in 0..matrix.len() {
-            transposed[i][j] = matrix[j][i];
-        }
+use nested_arrays::{transpose, Matrix};
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::process::ExitCode;
+
+/// Read the matrix in `path`, as Matrix Market if it ends in `.mtx` and as
+/// CSV otherwise, and write its transpose to stdout in the same format.
+fn transpose_file(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let input = BufReader::new(File::open(path)?);
+    let market = path.ends_with(".mtx");
+    let matrix: Matrix<f64> = if market {
+        Matrix::read_matrix_market(input)?
+    } else {
+        Matrix::read_csv(input)?
+    };
+    let mut out = io::stdout().lock();
+    if market {
+        matrix.transpose().write_matrix_market(&mut out)?;
+    } else {
+        matrix.transpose().write_csv(&mut out)?;
     }
-    transposed
+    out.flush()?;
+    Ok(())
 }
 
-fn main() {
+fn main() -> ExitCode {
+    if let Some(path) = std::env::args().nth(1) {
+        return match transpose_file(&path) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("nested_arrays: {path}: {err}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
     let matrix = [
         [101, 102, 103], // <-- the comment makes rustfmt add a newline
         [201, 202, 203],
@@ -18,20 +43,9 @@ fn main() {
     println!("matrix: {:#?}", matrix);
     let transposed = transpose(matrix);
     println!("transposed: {:#?}", transposed);
-}
-
-#[test]
-fn test_transpose() {
-    let actual = [
-        [1, 2, 3], // <-- the comment makes rustfmt add a newline
-        [4, 5, 6],
-        [7, 8, 9],
-    ];
-    let expected = [
-        [1, 4, 7], // <-- the comment makes rustfmt add a newline
-        [2, 5, 8],
-        [3, 6, 9],
-    ];
 
-    assert_eq!(transpose(actual), expected);
+    let matrix = Matrix::from(matrix);
+    println!("matrix:\n{matrix}");
+    println!("transposed:\n{}", matrix.transpose());
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,388 @@
+use std::ops::Mul;
+
+use crate::{Matrix, MatrixError, Scalar};
+
+/// A sparse matrix in compressed sparse row (CSR) format. Only the non-zero
+/// elements are stored, row by row and sorted by column within each row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T> {
+    rows: usize,
+    cols: usize,
+    /// The elements of row `i` are at `row_ptr[i]..row_ptr[i + 1]`.
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// A sparse matrix in compressed sparse column (CSC) format, the column-major
+/// counterpart of `CsrMatrix`.
+///
+/// The CSC arrays of a matrix are the CSR arrays of its transpose, so
+/// converting between the two formats is a transpose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T> {
+    /// The transpose, whose rows are the columns of this matrix.
+    transpose: CsrMatrix<T>,
+}
+
+impl<T> CsrMatrix<T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The shape as `(rows, cols)`.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The number of stored elements.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The non-zero elements of a row as `(col, value)` pairs.
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, &T)> {
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+        self.col_idx[range.clone()]
+            .iter()
+            .copied()
+            .zip(&self.values[range])
+    }
+}
+
+impl<T: Copy> CsrMatrix<T> {
+    /// Transpose with a counting sort over the columns, in
+    /// O(rows + cols + nnz). Walking the rows in order keeps every row of the
+    /// result sorted.
+    pub fn transpose(&self) -> Self {
+        let mut row_ptr = vec![0; self.cols + 1];
+        for &col in &self.col_idx {
+            row_ptr[col + 1] += 1;
+        }
+        for col in 0..self.cols {
+            row_ptr[col + 1] += row_ptr[col];
+        }
+        // The next free slot in each row of the result.
+        let mut next = row_ptr.clone();
+        let mut col_idx = vec![0; self.nnz()];
+        let mut values = self.values.clone();
+        for row in 0..self.rows {
+            for (col, &value) in self.row(row) {
+                col_idx[next[col]] = row;
+                values[next[col]] = value;
+                next[col] += 1;
+            }
+        }
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+
+    /// Convert to column-major storage of the same matrix.
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        CscMatrix {
+            transpose: self.transpose(),
+        }
+    }
+}
+
+impl<T: Scalar> CsrMatrix<T> {
+    /// Create a matrix from `(row, col, value)` triplets in any order.
+    /// Duplicate entries are summed, and entries that end up as zero are not
+    /// stored.
+    pub fn from_triplets(
+        rows: usize,
+        cols: usize,
+        triplets: &[(usize, usize, T)],
+    ) -> Result<Self, MatrixError> {
+        if let Some(&(row, col, _)) = triplets.iter().find(|&&(r, c, _)| r >= rows || c >= cols) {
+            return Err(MatrixError::OutOfBounds {
+                row,
+                col,
+                rows,
+                cols,
+            });
+        }
+        let mut sorted = triplets.to_vec();
+        sorted.sort_by_key(|&(row, col, _)| (row, col));
+
+        let mut row_ptr = vec![0; rows + 1];
+        let mut col_idx: Vec<usize> = Vec::new();
+        let mut values: Vec<T> = Vec::new();
+        let mut entries = sorted.into_iter().peekable();
+        while let Some((row, col, mut value)) = entries.next() {
+            while let Some(&(_, _, duplicate)) =
+                entries.peek().filter(|&&(r, c, _)| (r, c) == (row, col))
+            {
+                value = value + duplicate;
+                entries.next();
+            }
+            if value != T::ZERO {
+                row_ptr[row + 1] += 1;
+                col_idx.push(col);
+                values.push(value);
+            }
+        }
+        for row in 0..rows {
+            row_ptr[row + 1] += row_ptr[row];
+        }
+        Ok(Self {
+            rows,
+            cols,
+            row_ptr,
+            col_idx,
+            values,
+        })
+    }
+
+    /// The element at `(row, col)`, or `None` if it is out of bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+        Some(match self.col_idx[range.clone()].binary_search(&col) {
+            Ok(i) => self.values[range.start + i],
+            Err(_) => T::ZERO,
+        })
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        let mut dense = Matrix::zeros(self.rows, self.cols);
+        for row in 0..self.rows {
+            for (col, &value) in self.row(row) {
+                dense[(row, col)] = value;
+            }
+        }
+        dense
+    }
+}
+
+impl<T: Scalar> From<&Matrix<T>> for CsrMatrix<T> {
+    fn from(dense: &Matrix<T>) -> Self {
+        let mut row_ptr = Vec::with_capacity(dense.rows() + 1);
+        let mut col_idx = Vec::new();
+        let mut values = Vec::new();
+        row_ptr.push(0);
+        for row in 0..dense.rows() {
+            for (col, &value) in dense.row(row).iter().enumerate() {
+                if value != T::ZERO {
+                    col_idx.push(col);
+                    values.push(value);
+                }
+            }
+            row_ptr.push(values.len());
+        }
+        Self {
+            rows: dense.rows(),
+            cols: dense.cols(),
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+}
+
+impl<T: Scalar> Mul<&Matrix<T>> for &CsrMatrix<T> {
+    type Output = Result<Matrix<T>, MatrixError>;
+
+    /// The product with a dense matrix, touching only the non-zero elements.
+    fn mul(self, other: &Matrix<T>) -> Self::Output {
+        if self.cols != other.rows() {
+            return Err(MatrixError::DimensionMismatch {
+                op: "multiply",
+                left: self.dims(),
+                right: other.dims(),
+            });
+        }
+        let mut product = Matrix::zeros(self.rows, other.cols());
+        for i in 0..self.rows {
+            for (k, &a) in self.row(i) {
+                for (j, &b) in other.row(k).iter().enumerate() {
+                    product[(i, j)] = product[(i, j)] + a * b;
+                }
+            }
+        }
+        Ok(product)
+    }
+}
+
+impl<T> CscMatrix<T> {
+    pub fn rows(&self) -> usize {
+        self.transpose.cols()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.transpose.rows()
+    }
+
+    /// The shape as `(rows, cols)`.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.rows(), self.cols())
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.transpose.nnz()
+    }
+
+    /// The non-zero elements of a column as `(row, value)` pairs.
+    pub fn col(&self, col: usize) -> impl Iterator<Item = (usize, &T)> {
+        self.transpose.row(col)
+    }
+
+    /// Reinterpret as the CSR transpose without copying.
+    pub fn into_transpose(self) -> CsrMatrix<T> {
+        self.transpose
+    }
+}
+
+impl<T: Copy> CscMatrix<T> {
+    /// Convert to row-major storage of the same matrix.
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        self.transpose.transpose()
+    }
+}
+
+impl<T: Scalar> CscMatrix<T> {
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        self.transpose.get(col, row)
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        self.transpose.to_dense().transpose()
+    }
+}
+
+#[cfg(test)]
+fn random_sparse(rows: usize, cols: usize, nnz: usize, seed: u64) -> Vec<(usize, usize, i64)> {
+    let mut state = seed;
+    let mut next = || crate::xorshift(&mut state);
+    (0..nnz)
+        .map(|_| {
+            let row = next() as usize % rows;
+            let col = next() as usize % cols;
+            (row, col, next() as i64 % 10)
+        })
+        .collect()
+}
+
+#[test]
+fn test_from_triplets() {
+    let m = CsrMatrix::from_triplets(
+        3,
+        4,
+        &[(2, 3, 5), (0, 1, 1), (0, 1, 2), (1, 0, 4), (1, 2, 0)],
+    )
+    .unwrap();
+    // The duplicates are summed and the explicit zero is dropped.
+    assert_eq!(m.nnz(), 3);
+    assert_eq!(m.get(0, 1), Some(3));
+    assert_eq!(m.get(1, 2), Some(0));
+    assert_eq!(m.get(3, 0), None);
+    assert_eq!(m.row(1).collect::<Vec<_>>(), [(0, &4)]);
+    assert_eq!(
+        m.to_dense(),
+        Matrix::from([[0, 3, 0, 0], [4, 0, 0, 0], [0, 0, 0, 5]])
+    );
+    assert_eq!(
+        CsrMatrix::from_triplets(2, 2, &[(0, 0, 1), (0, 2, 1)]),
+        Err(MatrixError::OutOfBounds {
+            row: 0,
+            col: 2,
+            rows: 2,
+            cols: 2
+        })
+    );
+}
+
+#[test]
+fn test_dense_round_trip() {
+    let dense = Matrix::from([[0.0, 1.5, 0.0], [0.0, 0.0, 0.0], [2.0, 0.0, -1.0]]);
+    let sparse = CsrMatrix::from(&dense);
+    assert_eq!(sparse.nnz(), 3);
+    assert_eq!(sparse.to_dense(), dense);
+    assert_eq!(sparse.to_csc().to_dense(), dense);
+    assert_eq!(sparse.to_csc().to_csr(), sparse);
+}
+
+#[test]
+fn test_empty_round_trip() {
+    for (rows, cols) in [(3, 0), (0, 3), (0, 0)] {
+        let dense = Matrix::<i32>::zeros(rows, cols);
+        let sparse = CsrMatrix::from(&dense);
+        assert_eq!(sparse, CsrMatrix::from_triplets(rows, cols, &[]).unwrap());
+        assert_eq!(sparse.to_dense(), dense);
+        assert_eq!(sparse.transpose().to_dense(), dense.transpose());
+        assert_eq!(sparse.to_csc().to_dense(), dense);
+        for row in 0..rows {
+            assert_eq!(sparse.row(row).count(), 0);
+        }
+        let mut out = Vec::new();
+        sparse.write_matrix_market(&mut out).unwrap();
+        assert_eq!(CsrMatrix::read_matrix_market(&out[..]).unwrap(), sparse);
+    }
+}
+
+#[test]
+fn test_transpose_matches_dense() {
+    let array = [[0, 7, 0, 0, 1], [0, 0, 0, 0, 0], [3, 0, 0, 9, 0]];
+    let sparse = CsrMatrix::from(&Matrix::from(array));
+    let expected = Matrix::from(crate::transpose(array));
+    assert_eq!(sparse.transpose().to_dense(), expected);
+    assert_eq!(sparse.to_csc().into_transpose().to_dense(), expected);
+    assert_eq!(
+        sparse.to_csc().col(3).collect::<Vec<_>>(),
+        [(2, &9)],
+        "column 3 of the CSC form"
+    );
+
+    for (i, (rows, cols)) in [(1, 1), (1, 40), (40, 1), (17, 23), (100, 60)]
+        .into_iter()
+        .enumerate()
+    {
+        let triplets = random_sparse(rows, cols, rows * cols / 10 + 1, i as u64 + 1);
+        let sparse = CsrMatrix::from_triplets(rows, cols, &triplets).unwrap();
+        let dense = sparse.to_dense();
+        assert_eq!(CsrMatrix::from(&dense), sparse);
+        assert_eq!(sparse.transpose().to_dense(), dense.transpose());
+        assert_eq!(sparse.transpose().transpose(), sparse);
+        let csc = sparse.to_csc();
+        assert_eq!(csc.to_dense(), dense);
+        for row in 0..rows {
+            for col in 0..cols {
+                assert_eq!(csc.get(row, col), Some(dense[(row, col)]));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_multiply_matches_dense() {
+    let triplets = random_sparse(30, 20, 60, 42);
+    let sparse = CsrMatrix::from_triplets(30, 20, &triplets).unwrap();
+    let dense = sparse.to_dense();
+    let other = Matrix::from_vec(20, 7, (0..140).map(|x| x % 13 - 6).collect()).unwrap();
+    assert_eq!(&sparse * &other, &dense * &other);
+    // (A * B)^T = B^T * A^T, with A^T from the sparse transpose.
+    let product = (&sparse * &other).unwrap();
+    assert_eq!(
+        &other.transpose() * &sparse.transpose().to_dense(),
+        Ok(product.transpose())
+    );
+    assert_eq!(
+        &sparse * &dense,
+        Err(MatrixError::DimensionMismatch {
+            op: "multiply",
+            left: (30, 20),
+            right: (30, 20)
+        })
+    );
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn imgrot(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_imgrot"))
+        .args(args)
+        .output()
+        .expect("failed to run imgrot")
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("imgrot-{}-{name}", std::process::id()))
+}
+
+/// A 3x2 PPM with one distinct color per pixel.
+const PPM: &[u8] = b"P6\n3 2\n255\n\
+    \x01\x01\x01\x02\x02\x02\x03\x03\x03\
+    \x04\x04\x04\x05\x05\x05\x06\x06\x06";
+
+#[test]
+fn test_rotate_ppm() {
+    let input = temp_path("in.ppm");
+    let output = temp_path("out.ppm");
+    fs::write(&input, PPM).unwrap();
+    let result = imgrot(&[
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--rotate",
+        "90",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(
+        fs::read(&output).unwrap(),
+        b"P6\n2 3\n255\n\
+          \x04\x04\x04\x01\x01\x01\
+          \x05\x05\x05\x02\x02\x02\
+          \x06\x06\x06\x03\x03\x03"
+    );
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn test_flips_undo_half_turn() {
+    let input = temp_path("flip-in.ppm");
+    let output = temp_path("flip-out.ppm");
+    fs::write(&input, PPM).unwrap();
+    let result = imgrot(&[
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--rotate",
+        "180",
+        "--flip",
+        "h",
+        "--flip",
+        "v",
+    ]);
+    assert!(result.status.success());
+    assert_eq!(fs::read(&output).unwrap(), PPM);
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn test_invalid_arguments() {
+    for args in [
+        &["in.ppm", "out.ppm", "--rotate", "45"][..],
+        &["in.ppm", "out.ppm", "--flip"],
+        &["in.ppm"],
+    ] {
+        let result = imgrot(args);
+        assert_eq!(result.status.code(), Some(2), "{args:?}");
+        assert!(String::from_utf8_lossy(&result.stderr).starts_with("Usage: imgrot"));
+    }
+
+    let missing = temp_path("missing.ppm");
+    let result = imgrot(&[missing.to_str().unwrap(), "out.ppm"]);
+    assert_eq!(result.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&result.stderr).starts_with("imgrot: "));
+}
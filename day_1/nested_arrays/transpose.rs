@@ -0,0 +1,129 @@
+/// Edge length of the square tiles used by `transpose_blocked`. A 32x32 tile
+/// of `f64` takes 8 KiB, so a source and a destination tile fit into L1.
+pub const BLOCK_SIZE: usize = 32;
+
+/// Transpose the `rows` x `cols` matrix `src` into `dst` with a plain double
+/// loop. The writes to `dst` jump by `rows` elements, so this thrashes the
+/// cache for large matrices.
+pub fn transpose_naive<T: Copy>(src: &[T], rows: usize, cols: usize, dst: &mut [T]) {
+    assert_eq!(src.len(), rows * cols, "source does not match the shape");
+    assert_eq!(dst.len(), src.len(), "destination has the wrong size");
+    for i in 0..rows {
+        for j in 0..cols {
+            dst[j * rows + i] = src[i * cols + j];
+        }
+    }
+}
+
+/// Transpose the `rows` x `cols` matrix `src` into `dst` tile by tile, so
+/// that both the reads and the writes stay within a few cache lines.
+pub fn transpose_blocked<T: Copy>(src: &[T], rows: usize, cols: usize, dst: &mut [T]) {
+    assert_eq!(src.len(), rows * cols, "source does not match the shape");
+    assert_eq!(dst.len(), src.len(), "destination has the wrong size");
+    for row_block in (0..rows).step_by(BLOCK_SIZE) {
+        for col_block in (0..cols).step_by(BLOCK_SIZE) {
+            for i in row_block..rows.min(row_block + BLOCK_SIZE) {
+                for j in col_block..cols.min(col_block + BLOCK_SIZE) {
+                    dst[j * rows + i] = src[i * cols + j];
+                }
+            }
+        }
+    }
+}
+
+/// Transpose the `rows` x `cols` matrix in `data` in place; afterwards it
+/// holds the `cols` x `rows` transpose in row-major order.
+///
+/// The element at index k moves to `k * rows mod (len - 1)`. Each cycle of
+/// that permutation is followed with swaps, and a bitmap (one bit per
+/// element) records which positions are already in place.
+pub fn transpose_slice_in_place<T>(data: &mut [T], rows: usize, cols: usize) {
+    assert_eq!(data.len(), rows * cols, "data does not match the shape");
+    let len = data.len();
+    if rows <= 1 || cols <= 1 {
+        // A single row or column has the same layout as its transpose.
+        return;
+    }
+    let target = |k: usize| (k * rows) % (len - 1);
+    let mut done = vec![0u64; len.div_ceil(64)];
+    // Mark position k as in place, returning whether it already was.
+    let mut mark = |k: usize| {
+        let bit = 1 << (k % 64);
+        let seen = done[k / 64] & bit != 0;
+        done[k / 64] |= bit;
+        seen
+    };
+    // The first and last elements never move.
+    for start in 1..len - 1 {
+        if mark(start) {
+            continue;
+        }
+        // data[start] holds the element that belongs at target(start).
+        let mut next = target(start);
+        while next != start {
+            data.swap(start, next);
+            mark(next);
+            next = target(next);
+        }
+    }
+}
+
+#[cfg(test)]
+const SHAPES: [(usize, usize); 9] = [
+    (0, 0),
+    (0, 5),
+    (1, 1),
+    (1, 7),
+    (7, 1),
+    (2, 3),
+    (7, 13),
+    (64, 33),
+    (100, 101),
+];
+
+#[test]
+fn test_blocked_matches_naive() {
+    for (rows, cols) in SHAPES {
+        let src: Vec<u32> = (0..(rows * cols) as u32).collect();
+        let mut expected = vec![0; src.len()];
+        let mut actual = vec![0; src.len()];
+        transpose_naive(&src, rows, cols, &mut expected);
+        transpose_blocked(&src, rows, cols, &mut actual);
+        assert_eq!(actual, expected, "{rows}x{cols}");
+    }
+}
+
+#[test]
+fn test_in_place_matches_naive() {
+    for (rows, cols) in SHAPES {
+        let src: Vec<u32> = (0..(rows * cols) as u32).collect();
+        let mut expected = vec![0; src.len()];
+        transpose_naive(&src, rows, cols, &mut expected);
+        let mut data = src.clone();
+        transpose_slice_in_place(&mut data, rows, cols);
+        assert_eq!(data, expected, "{rows}x{cols}");
+        // Transposing back restores the original.
+        transpose_slice_in_place(&mut data, cols, rows);
+        assert_eq!(data, src, "{rows}x{cols}");
+    }
+}
+
+#[test]
+fn test_in_place_non_copy() {
+    let mut data: Vec<String> = ["a", "b", "c", "d", "e", "f"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    transpose_slice_in_place(&mut data, 2, 3);
+    assert_eq!(data, ["a", "d", "b", "e", "c", "f"]);
+}
+
+#[test]
+fn test_matches_array_transpose() {
+    let array = [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]];
+    let flat: Vec<i32> = array.iter().flatten().copied().collect();
+    let mut transposed = vec![0; flat.len()];
+    transpose_blocked(&flat, 3, 4, &mut transposed);
+    let expected: Vec<i32> = crate::transpose(array).iter().flatten().copied().collect();
+    assert_eq!(transposed, expected);
+}